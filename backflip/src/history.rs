@@ -0,0 +1,136 @@
+use std::collections::VecDeque;
+
+/// How many buffer states a session remembers before dropping the oldest.
+const HISTORY_LIMIT: usize = 100;
+
+pub struct Entry {
+    pub label: String,
+    pub text: String,
+}
+
+/// A bounded undo/redo stack of buffer states. `current` points at the entry the buffer is
+/// showing; entries after it are available to redo until something new is pushed.
+pub struct History {
+    entries: VecDeque<Entry>,
+    current: usize,
+}
+
+impl History {
+    pub fn new(text: String) -> Self {
+        let mut entries = VecDeque::new();
+        entries.push_back(Entry {
+            label: "captured clipboard".to_string(),
+            text,
+        });
+        History {
+            entries,
+            current: 0,
+        }
+    }
+
+    pub fn push(&mut self, label: &str, text: String) {
+        self.entries.truncate(self.current + 1);
+        self.entries.push_back(Entry {
+            label: label.to_string(),
+            text,
+        });
+        if self.entries.len() > HISTORY_LIMIT {
+            self.entries.pop_front();
+        }
+        self.current = self.entries.len() - 1;
+    }
+
+    pub fn current(&self) -> &Entry {
+        &self.entries[self.current]
+    }
+
+    pub fn current_index(&self) -> usize {
+        self.current
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = &Entry> {
+        self.entries.iter()
+    }
+
+    pub fn undo(&mut self) -> Option<&str> {
+        if self.current == 0 {
+            return None;
+        }
+        self.jump(self.current - 1)
+    }
+
+    pub fn redo(&mut self) -> Option<&str> {
+        self.jump(self.current + 1)
+    }
+
+    pub fn jump(&mut self, index: usize) -> Option<&str> {
+        let entry = self.entries.get(index)?;
+        self.current = index;
+        Some(&entry.text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn labels(history: &History) -> Vec<&str> {
+        history
+            .entries()
+            .map(|entry| entry.label.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn undo_and_redo_walk_the_entries() {
+        let mut history = History::new("a".to_string());
+        history.push("b", "ab".to_string());
+        history.push("c", "abc".to_string());
+        assert_eq!(history.undo(), Some("ab"));
+        assert_eq!(history.undo(), Some("a"));
+        assert_eq!(history.undo(), None);
+        assert_eq!(history.current_index(), 0);
+        assert_eq!(history.redo(), Some("ab"));
+        assert_eq!(history.redo(), Some("abc"));
+        assert_eq!(history.redo(), None);
+        assert_eq!(history.current().text, "abc");
+    }
+
+    #[test]
+    fn pushing_drops_the_redo_entries() {
+        let mut history = History::new("a".to_string());
+        history.push("b", "b".to_string());
+        history.push("c", "c".to_string());
+        history.undo();
+        history.undo();
+        history.push("d", "d".to_string());
+        assert_eq!(labels(&history), ["captured clipboard", "d"]);
+        assert_eq!(history.redo(), None);
+    }
+
+    #[test]
+    fn evicts_the_oldest_entry_at_the_limit() {
+        let mut history = History::new("0".to_string());
+        for i in 1..=HISTORY_LIMIT {
+            history.push(&i.to_string(), i.to_string());
+        }
+        assert_eq!(history.entries().count(), HISTORY_LIMIT);
+        assert_eq!(history.entries().next().unwrap().text, "1");
+        assert_eq!(history.current_index(), HISTORY_LIMIT - 1);
+        assert_eq!(history.current().text, HISTORY_LIMIT.to_string());
+    }
+
+    #[test]
+    fn jumps_to_an_entry() {
+        let mut history = History::new("a".to_string());
+        history.push("b", "b".to_string());
+        history.push("c", "c".to_string());
+        assert_eq!(history.jump(0), Some("a"));
+        assert_eq!(history.jump(2), Some("c"));
+        assert_eq!(history.jump(3), None);
+        assert_eq!(history.current_index(), 2);
+        history.jump(1);
+        history.push("e", "e".to_string());
+        assert_eq!(labels(&history), ["captured clipboard", "b", "e"]);
+    }
+}
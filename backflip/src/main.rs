@@ -1,6 +1,12 @@
+mod history;
+mod session;
+mod views;
+
 use arboard::Clipboard;
+use backflip_transforms::Registry;
 use crossbeam_channel::Receiver;
 use global_hotkey::{GlobalHotKeyEventReceiver, HotKeyState};
+use std::path::PathBuf;
 use std::sync::{mpsc, Arc, RwLock};
 use std::thread::{self, JoinHandle};
 
@@ -17,6 +23,8 @@ use std::{ptr::null_mut, time::Duration};
 use eframe::egui::{self, Context, Key, ViewportCommand, ViewportId};
 use tray_icon::{TrayIconBuilder, TrayIconEvent, TrayIconEventReceiver};
 
+use session::Session;
+
 fn main() -> Result<(), eframe::Error> {
     let path = concat!(env!("CARGO_MANIFEST_DIR"), "/assets/icon.png");
     let icon = load_icon(std::path::Path::new(path));
@@ -65,6 +73,7 @@ fn main() -> Result<(), eframe::Error> {
         sessions: vec![],
         wakeup_thread: None,
         wakeup_requests: None,
+        transforms: Registry::with_builtins(),
     };
 
     eframe::run_native(
@@ -94,13 +103,7 @@ struct MyApp {
     sessions: Vec<Option<Session>>,
     wakeup_thread: Option<JoinHandle<()>>,
     wakeup_requests: Option<Receiver<HotkeyPress>>,
-}
-
-struct Session {
-    captured_clipboard: String,
-    viewport_id: ViewportId,
-    title: String,
-    requested_focus: bool,
+    transforms: Registry,
 }
 
 impl eframe::App for MyApp {
@@ -135,6 +138,7 @@ impl eframe::App for MyApp {
             }
         }
 
+        let transforms = &mut self.transforms;
         for session in &mut self.sessions {
             let mut closing = false;
             if let Some(session) = session {
@@ -142,49 +146,9 @@ impl eframe::App for MyApp {
                     session.viewport_id,
                     egui::ViewportBuilder::default()
                         .with_title(&session.title)
-                        .with_inner_size([500.0, 200.0]),
+                        .with_inner_size([900.0, 400.0]),
                     |ctx, class| {
-                        egui::CentralPanel::default().show(ctx, |ui| {
-                            ui.text_edit_multiline(&mut session.captured_clipboard);
-                            ui.label("S: serialize json. X: deserialize json");
-                            ui.label("D: reverse slashes");
-                            ui.label("enter: copy. escape: close.");
-                        });
-
-                        if ctx.input(|i| i.viewport().close_requested()) {
-                            closing = true;
-                        }
-
-                        if ctx.input(|i| i.key_released(Key::Escape)) {
-                            closing = true;
-                        }
-                        if ctx.input(|i| i.key_released(Key::Enter)) {
-                            let mut clipboard = Clipboard::new().unwrap();
-                            clipboard.set_text(&session.captured_clipboard).unwrap();
-                            closing = true;
-                        }
-
-                        if ctx.input(|i| i.key_released(Key::D)) {
-                            session.captured_clipboard = session
-                                .captured_clipboard
-                                .replace("\\", "THISWASABACKSLASH");
-                            session.captured_clipboard =
-                                session.captured_clipboard.replace("/", "\\");
-                            session.captured_clipboard =
-                                session.captured_clipboard.replace("THISWASABACKSLASH", "/");
-                        }
-
-                        if ctx.input(|i| i.key_released(Key::S)) {
-                            session.captured_clipboard =
-                                serde_json::to_string(&session.captured_clipboard).unwrap();
-                        }
-
-                        if ctx.input(|i| i.key_released(Key::A)) {
-                            match serde_json::from_str(&session.captured_clipboard) {
-                                Ok(s) => session.captured_clipboard = s,
-                                Err(_) => (),
-                            }
-                        }
+                        closing = session.ui(ctx, transforms);
                     },
                 );
                 if session.requested_focus {
@@ -217,15 +181,19 @@ impl eframe::App for MyApp {
 impl MyApp {
     pub fn new_session(&mut self) -> anyhow::Result<()> {
         let mut captured_clipboard: Option<String> = None;
+        let files: Vec<PathBuf>;
 
         #[cfg(target_os = "windows")]
         {
             use clipboard_win::{formats, get_clipboard, set_clipboard};
-            captured_clipboard = match clipboard_win::get_clipboard_string() {
-                Ok(s) => Some(s),
-                Err(_) => match clipboard_win::get_clipboard(formats::FileList) {
-                    Ok(files) => Some(files.join(",")),
-                    Err(_) => None,
+            (captured_clipboard, files) = match clipboard_win::get_clipboard_string() {
+                Ok(s) => (Some(s), vec![]),
+                Err(_) => match clipboard_win::get_clipboard::<Vec<String>, _>(formats::FileList) {
+                    Ok(files) => (
+                        Some(files.join("\n")),
+                        files.into_iter().map(PathBuf::from).collect(),
+                    ),
+                    Err(_) => (None, vec![]),
                 },
             };
         }
@@ -233,15 +201,16 @@ impl MyApp {
         {
             let mut clipboard = Clipboard::new().unwrap();
             captured_clipboard = clipboard.get_text()?;
+            files = vec![];
         }
 
         if let Some(c) = captured_clipboard {
-            let s = Session {
-                captured_clipboard: c,
-                viewport_id: ViewportId::from_hash_of(format!("session-{}", self.sessions.len())),
-                title: format!("backflip {}", self.sessions.len()),
-                requested_focus: true,
-            };
+            let mut s = Session::new(
+                c,
+                ViewportId::from_hash_of(format!("session-{}", self.sessions.len())),
+                format!("backflip {}", self.sessions.len()),
+            );
+            s.files = files;
             self.sessions.push(Some(s));
         }
         Ok(())
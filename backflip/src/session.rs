@@ -0,0 +1,953 @@
+use std::ops::Range;
+use std::path::PathBuf;
+use std::sync::mpsc;
+use std::time::Duration;
+
+use arboard::Clipboard;
+use backflip_transforms::{
+    hex, integer, jwt, replace::Replace, timestamp, url::UrlParts, Registry, Transform,
+    RESERVED_KEYS,
+};
+use eframe::egui::{
+    self,
+    text::{CCursor, CCursorRange},
+    Key, Modifiers, ViewportId,
+};
+
+use crate::history::History;
+use crate::views::{
+    self,
+    binary::BinaryView,
+    find::{self, FindBar},
+    jwt::JwtView,
+    list::ListView,
+    query::QueryView,
+    unicode::UnicodeView,
+};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Mode {
+    /// Single keys run transforms. The editor doesn't have focus.
+    Command,
+    /// The editor gets every keystroke. Transforms can still be run by holding alt.
+    Insert,
+}
+
+impl Mode {
+    /// The mode after a frame where a text field did or didn't have focus and escape was or
+    /// wasn't pressed, or `None` if the session should close. Escape leaves insert mode, and
+    /// closes the session from command mode.
+    fn next(self, editing: bool, escape: bool) -> Option<Mode> {
+        match (self, escape) {
+            (Mode::Command, true) => None,
+            (Mode::Insert, true) => Some(Mode::Command),
+            _ if editing => Some(Mode::Insert),
+            _ => Some(Mode::Command),
+        }
+    }
+}
+
+/// Looks up a key by name, ignoring case, so `space` finds `Key::Space` and `b` finds `Key::B`.
+fn key_named(name: &str) -> Option<Key> {
+    Key::from_name(name).or_else(|| {
+        Key::ALL
+            .iter()
+            .copied()
+            .find(|key| key.name().eq_ignore_ascii_case(name))
+    })
+}
+
+/// Whether `key` went down this frame. Every command key acts on the press, since that's when
+/// egui moves focus, and ignores key repeat so holding a key runs a transform once.
+fn pressed(input: &egui::InputState, key: Key) -> bool {
+    input.events.iter().any(|event| {
+        matches!(
+            event,
+            egui::Event::Key {
+                key: k,
+                pressed: true,
+                repeat: false,
+                ..
+            } if *k == key
+        )
+    })
+}
+
+pub enum View {
+    /// The buffer in a plain text editor.
+    Text,
+    /// The buffer parsed as a URL and shown as an editable table.
+    Url(UrlParts),
+    /// A jq-style query run live against the buffer.
+    Query(QueryView),
+    /// The buffer decoded as a JSON web token.
+    Jwt(JwtView),
+    /// A transform's output that isn't UTF-8, which the buffer can't hold.
+    Binary(BinaryView),
+    /// Every character in the buffer, with the invisible ones called out.
+    Unicode(UnicodeView),
+    /// The join list transform's options, with a live preview of its output.
+    List(ListView),
+}
+
+/// A transform running on a worker thread, and where its result will arrive.
+struct Pending {
+    label: String,
+    result: mpsc::Receiver<anyhow::Result<String>>,
+}
+
+pub struct Session {
+    pub captured_clipboard: String,
+    pub viewport_id: ViewportId,
+    pub title: String,
+    pub requested_focus: bool,
+    pub error: Option<String>,
+    pub mode: Mode,
+    pub history: History,
+    pub view: View,
+    /// The characters selected in the editor. While there is a selection, transforms only
+    /// touch that part of the buffer.
+    pub selection: Option<Range<usize>>,
+    /// Set when the selection was changed from outside the editor, so the editor's own cursor
+    /// state needs updating to match.
+    selection_changed: bool,
+    /// The find/replace bar, while it's open.
+    pub find: Option<FindBar>,
+    /// The files the clipboard held, if it held a file list. The buffer starts out as their
+    /// paths, one per line.
+    pub files: Vec<PathBuf>,
+    /// A transform still working on the captured files.
+    pending: Option<Pending>,
+    /// The timestamps in the buffer, and the text they were found in, so hovering doesn't
+    /// search the whole buffer every frame.
+    timestamps: (String, Vec<timestamp::Found>),
+}
+
+impl Session {
+    pub fn new(captured_clipboard: String, viewport_id: ViewportId, title: String) -> Self {
+        Session {
+            history: History::new(captured_clipboard.clone()),
+            captured_clipboard,
+            viewport_id,
+            title,
+            requested_focus: true,
+            error: None,
+            mode: Mode::Command,
+            view: View::Text,
+            selection: None,
+            selection_changed: false,
+            find: None,
+            files: vec![],
+            pending: None,
+            timestamps: (String::new(), vec![]),
+        }
+    }
+
+    /// The captured files, as long as the buffer is still the listing of them.
+    fn file_list(&self) -> Option<&[PathBuf]> {
+        let listing = self
+            .files
+            .iter()
+            .map(|path| path.display().to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+        (!self.files.is_empty() && self.captured_clipboard == listing).then_some(&self.files)
+    }
+
+    /// The timestamps in the buffer, only searched for again when it has changed.
+    fn timestamps(&mut self) -> &[timestamp::Found] {
+        if self.timestamps.0 != self.captured_clipboard {
+            let found = timestamp::find(&self.captured_clipboard);
+            self.timestamps = (self.captured_clipboard.clone(), found);
+        }
+        &self.timestamps.1
+    }
+
+    /// Byte offsets of the selection in the buffer.
+    fn selected_bytes(&self) -> Option<Range<usize>> {
+        let chars = self.selection.clone()?;
+        let byte = |index| {
+            self.captured_clipboard
+                .char_indices()
+                .nth(index)
+                .map_or(self.captured_clipboard.len(), |(i, _)| i)
+        };
+        Some(byte(chars.start)..byte(chars.end))
+    }
+
+    /// Runs `transform` on the selection if there is one, otherwise on the whole buffer.
+    pub fn apply(&mut self, transform: &dyn Transform) {
+        self.commit_edit();
+        if let Some(job) = self
+            .file_list()
+            .and_then(|files| transform.apply_files(files))
+        {
+            let (sender, receiver) = mpsc::channel();
+            std::thread::spawn(move || {
+                // The session may have closed or started another job, which is fine.
+                _ = sender.send(job());
+            });
+            self.pending = Some(Pending {
+                label: transform.name().to_string(),
+                result: receiver,
+            });
+            self.error = None;
+            return;
+        }
+        let bytes = self.selected_bytes();
+        let input = match bytes.clone() {
+            Some(bytes) => &self.captured_clipboard[bytes],
+            None => &self.captured_clipboard[..],
+        };
+        let result = match transform.apply_bytes(input) {
+            Some(Ok(decoded)) => match String::from_utf8(decoded) {
+                Ok(s) => Ok(s),
+                Err(e) => {
+                    self.view =
+                        View::Binary(BinaryView::new(transform.name(), e.into_bytes(), bytes));
+                    self.error = None;
+                    return;
+                }
+            },
+            Some(Err(e)) => Err(e),
+            None => transform.apply(input),
+        };
+        match result {
+            Ok(s) => self.splice(transform.name(), bytes, s),
+            Err(e) => self.error = Some(format!("{}: {}", transform.name(), e)),
+        }
+    }
+
+    /// Puts `s` in place of the byte range `bytes` of the buffer, or the whole buffer if that's
+    /// `None`. A replaced range stays selected so transforms can be chained on it.
+    fn splice(&mut self, label: &str, bytes: Option<Range<usize>>, s: String) {
+        let Some(bytes) = bytes else {
+            self.replace(label, s);
+            return;
+        };
+        let start = self.captured_clipboard[..bytes.start].chars().count();
+        let end = start + s.chars().count();
+        let mut text = self.captured_clipboard.clone();
+        text.replace_range(bytes, &s);
+        self.replace(label, text);
+        self.set_selection(Some(start..end));
+    }
+
+    /// Replaces the buffer, recording it in the history under `label`.
+    pub fn replace(&mut self, label: &str, text: String) {
+        self.commit_edit();
+        self.history.push(label, text.clone());
+        self.captured_clipboard = text;
+        self.error = None;
+        self.set_selection(None);
+    }
+
+    pub fn set_selection(&mut self, selection: Option<Range<usize>>) {
+        self.selection = selection;
+        self.selection_changed = true;
+    }
+
+    /// Registers the find bar's pattern and replacement as a transform of their own. Saved
+    /// transforms only live in the registry, so they last until backflip quits.
+    fn save_replacement(&mut self, transforms: &mut Registry) {
+        let Some(find) = &mut self.find else {
+            return;
+        };
+        let name = find.name.trim().to_string();
+        let key = find.key.trim().to_string();
+        let bound = key_named(&key);
+        let result = if name.is_empty() {
+            Err("give the transform a name".to_string())
+        } else if transforms.get(&name).is_some() {
+            Err(format!("there's already a transform called {}", name))
+        } else if !key.is_empty() && bound.is_none() {
+            Err(format!("{} isn't a key", key))
+        } else if bound.is_some_and(|bound| {
+            RESERVED_KEYS
+                .iter()
+                .any(|&key| Key::from_name(key) == Some(bound))
+                || transforms
+                    .iter()
+                    .any(|t| t.default_key().and_then(Key::from_name) == Some(bound))
+        }) {
+            Err(format!("{} is already used", key))
+        } else {
+            find.rule().map_err(|e| e.to_string())
+        };
+        match result {
+            Ok(rule) => {
+                transforms.register(Replace {
+                    description: format!("replace {} with {}", find.pattern, find.replacement),
+                    name,
+                    key: bound.map(|bound| bound.name().to_string()),
+                    rules: vec![rule],
+                });
+                find.name.clear();
+                find.key.clear();
+                find.error = None;
+            }
+            Err(e) => find.error = Some(e),
+        }
+    }
+
+    pub fn explode_url(&mut self) {
+        match UrlParts::parse(&self.captured_clipboard) {
+            Ok(parts) => {
+                self.commit_edit();
+                self.view = View::Url(parts);
+                self.error = None;
+            }
+            Err(e) => self.error = Some(format!("explode url: {}", e)),
+        }
+    }
+
+    pub fn inspect_jwt(&mut self) {
+        match jwt::decode(&self.captured_clipboard) {
+            Ok(token) => {
+                self.commit_edit();
+                self.view = View::Jwt(JwtView::new(token));
+                self.error = None;
+            }
+            Err(e) => self.error = Some(format!("inspect jwt: {}", e)),
+        }
+    }
+
+    /// Records typing in the editor as a single history entry.
+    fn commit_edit(&mut self) {
+        if self.captured_clipboard != self.history.current().text {
+            self.history
+                .push("manual edit", self.captured_clipboard.clone());
+        }
+    }
+
+    pub fn undo(&mut self) {
+        self.commit_edit();
+        if let Some(text) = self.history.undo() {
+            self.captured_clipboard = text.to_string();
+            self.set_selection(None);
+        }
+    }
+
+    pub fn redo(&mut self) {
+        self.commit_edit();
+        if let Some(text) = self.history.redo() {
+            self.captured_clipboard = text.to_string();
+            self.set_selection(None);
+        }
+    }
+
+    pub fn jump(&mut self, index: usize) {
+        self.commit_edit();
+        if let Some(text) = self.history.jump(index) {
+            self.captured_clipboard = text.to_string();
+            self.set_selection(None);
+        }
+    }
+
+    /// Puts the result of the worker thread's transform in the buffer once it's done.
+    fn poll_pending(&mut self, ctx: &egui::Context) {
+        let Some(pending) = self.pending.take() else {
+            return;
+        };
+        match pending.result.try_recv() {
+            Ok(Ok(s)) => self.replace(&pending.label, s),
+            Ok(Err(e)) => self.error = Some(format!("{}: {}", pending.label, e)),
+            Err(mpsc::TryRecvError::Empty) => {
+                self.pending = Some(pending);
+                ctx.request_repaint_after(Duration::from_millis(100));
+            }
+            Err(mpsc::TryRecvError::Disconnected) => {
+                self.error = Some(format!("{}: the worker thread stopped", pending.label));
+            }
+        }
+    }
+
+    /// Draws the session and handles its keys. Returns true when the session should close.
+    pub fn ui(&mut self, ctx: &egui::Context, transforms: &mut Registry) -> bool {
+        self.poll_pending(ctx);
+        let closing = match self.view {
+            View::Text => self.text_ui(ctx, transforms),
+            View::Url(_) => self.url_ui(ctx),
+            View::Query(_) => self.query_ui(ctx),
+            View::Jwt(_) => self.jwt_ui(ctx),
+            View::Binary(_) => self.binary_ui(ctx),
+            View::Unicode(_) => self.unicode_ui(ctx, transforms),
+            View::List(_) => self.list_ui(ctx, transforms),
+        };
+        closing || ctx.input(|i| i.viewport().close_requested())
+    }
+
+    fn text_ui(&mut self, ctx: &egui::Context, transforms: &mut Registry) -> bool {
+        let mut closing = false;
+        // egui drops focus from the editor as soon as escape is pressed, so remember which mode
+        // we were in before drawing anything.
+        let mode = self.mode;
+
+        let mut jump_to = None;
+        egui::SidePanel::right("history").show(ctx, |ui| {
+            ui.strong("history");
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                let current = self.history.current_index();
+                for (index, entry) in self.history.entries().enumerate() {
+                    if ui
+                        .selectable_label(index == current, &entry.label)
+                        .clicked()
+                    {
+                        jump_to = Some(index);
+                    }
+                }
+            });
+        });
+        if let Some(index) = jump_to {
+            self.jump(index);
+        }
+
+        // When the selection, or failing that the whole buffer, is an integer, show what it
+        // looks like in other bases and widths.
+        let number = match self.selected_bytes() {
+            Some(bytes) => &self.captured_clipboard[bytes],
+            None => self.captured_clipboard.as_str(),
+        };
+        if let Some(value) = integer::parse(number.trim()) {
+            egui::SidePanel::right("integer").show(ctx, |ui| {
+                ui.strong("integer");
+                egui::ScrollArea::vertical().show(ui, |ui| views::integer::show(ui, value));
+            });
+        }
+
+        let mut clicked = None;
+        let mut explode_url = mode == Mode::Command && ctx.input(|i| pressed(i, Key::L));
+        let mut query_json = mode == Mode::Command && ctx.input(|i| pressed(i, Key::Q));
+        let mut inspect_jwt = mode == Mode::Command && ctx.input(|i| pressed(i, Key::J));
+        let mut inspect_unicode = mode == Mode::Command && ctx.input(|i| pressed(i, Key::G));
+        let mut format_list = mode == Mode::Command && ctx.input(|i| pressed(i, Key::W));
+        if mode == Mode::Command && ctx.input(|i| pressed(i, Key::F)) {
+            self.find.get_or_insert_with(FindBar::new).focus();
+        }
+        egui::SidePanel::left("transforms").show(ctx, |ui| {
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                ui.strong("views");
+                explode_url |= ui.button("L: explode url").clicked();
+                query_json |= ui.button("Q: query json").clicked();
+                inspect_jwt |= ui.button("J: inspect jwt").clicked();
+                inspect_unicode |= ui.button("G: inspect characters").clicked();
+                format_list |= ui.button("W: format list").clicked();
+                ui.strong("transforms");
+                for (index, transform) in transforms.iter_mut().enumerate() {
+                    let text = match transform.default_key() {
+                        Some(key) if mode == Mode::Insert => {
+                            format!("alt+{}: {}", key, transform.description())
+                        }
+                        Some(key) => format!("{}: {}", key, transform.description()),
+                        None => transform.description().to_string(),
+                    };
+                    if ui.button(text).on_hover_text(transform.name()).clicked() {
+                        clicked = Some(index);
+                    }
+                    let name = transform.name().to_string();
+                    let settings = transform.settings();
+                    if !settings.is_empty() {
+                        egui::CollapsingHeader::new("options")
+                            .id_source(&name)
+                            .show(ui, |ui| views::settings::show(ui, &name, settings));
+                    }
+                }
+            });
+        });
+        if let Some(transform) = clicked.and_then(|index| transforms.iter().nth(index)) {
+            self.apply(transform);
+        }
+        if explode_url {
+            self.explode_url();
+            return false;
+        }
+        if query_json {
+            self.commit_edit();
+            self.view = View::Query(QueryView::new());
+            return false;
+        }
+        if inspect_jwt {
+            self.inspect_jwt();
+            return false;
+        }
+        if inspect_unicode {
+            self.commit_edit();
+            self.view = View::Unicode(UnicodeView::new());
+            return false;
+        }
+        if format_list {
+            self.commit_edit();
+            self.view = View::List(ListView::new());
+            return false;
+        }
+
+        let mut action = find::Action::None;
+        if let Some(find) = &mut self.find {
+            egui::TopBottomPanel::top("find").show(ctx, |ui| {
+                action = find.show(ui, &self.captured_clipboard);
+            });
+        }
+        match action {
+            find::Action::None => (),
+            find::Action::ReplaceAll => match self.find.as_ref().map(FindBar::rule) {
+                Some(Ok(rule)) => self.apply(&Replace::new("find and replace", vec![rule])),
+                Some(Err(e)) => self.error = Some(format!("find and replace: {}", e)),
+                None => (),
+            },
+            find::Action::Save => self.save_replacement(transforms),
+            find::Action::Close => self.find = None,
+        }
+        let regex = self.find.as_ref().and_then(FindBar::regex).cloned();
+
+        egui::CentralPanel::default().show(ctx, |ui| {
+            match mode {
+                Mode::Command => ui.strong("-- COMMAND --"),
+                Mode::Insert => ui.colored_label(egui::Color32::LIGHT_GREEN, "-- INSERT --"),
+            };
+
+            let editor_id = egui::Id::new(self.viewport_id).with("buffer");
+            if self.selection_changed {
+                let mut state = egui::TextEdit::load_state(ctx, editor_id).unwrap_or_default();
+                state
+                    .cursor
+                    .set_char_range(self.selection.clone().map(|range| {
+                        CCursorRange::two(CCursor::new(range.start), CCursor::new(range.end))
+                    }));
+                egui::TextEdit::store_state(ctx, editor_id, state);
+                self.selection_changed = false;
+            }
+            let mut layouter = |ui: &egui::Ui, text: &str, wrap_width: f32| {
+                find::highlight(ui, text, regex.as_ref(), wrap_width)
+            };
+            let mut editor = egui::TextEdit::multiline(&mut self.captured_clipboard).id(editor_id);
+            if regex.is_some() {
+                editor = editor.layouter(&mut layouter);
+            }
+            let output = editor.show(ui);
+            let mut editor = output.response;
+            if let Some(pos) = editor.hover_pos() {
+                let index = output
+                    .galley
+                    .cursor_from_pos(pos - output.galley_pos)
+                    .ccursor
+                    .index;
+                let byte = self
+                    .captured_clipboard
+                    .char_indices()
+                    .nth(index)
+                    .map_or(self.captured_clipboard.len(), |(i, _)| i);
+                let hovered = self
+                    .timestamps()
+                    .iter()
+                    .find(|found| found.range.contains(&byte));
+                if let Some(found) = hovered {
+                    editor = editor.on_hover_text_at_pointer(timestamp::describe(found));
+                }
+            }
+            // The editor remembers its selection after losing focus, but only draws it while
+            // focused, so say so below.
+            if let Some(range) = output.state.cursor.char_range() {
+                let [start, end] = range.sorted();
+                self.selection = (start != end).then_some(start.index..end.index);
+            }
+            // Typing into an option field counts as insert mode too, so it can't trigger
+            // transforms.
+            let escape = ctx.input(|i| pressed(i, Key::Escape));
+            match mode.next(ctx.wants_keyboard_input(), escape) {
+                Some(next) => {
+                    if next == Mode::Command && mode == Mode::Insert {
+                        self.commit_edit();
+                    }
+                    self.mode = next;
+                }
+                None => closing = true,
+            }
+
+            if mode == Mode::Command && ctx.input(|i| pressed(i, Key::I)) {
+                editor.request_focus();
+            }
+
+            match mode {
+                Mode::Command => {
+                    ui.label("i: edit text. f: find and replace.");
+                    ui.label("ctrl+z / ctrl+shift+z: undo / redo.");
+                    ui.label("enter: copy. escape: close.");
+                    if let Some(selection) = self.selection.clone() {
+                        ui.horizontal(|ui| {
+                            ui.label(format!(
+                                "transforms apply to the {} selected characters only.",
+                                selection.len()
+                            ));
+                            if ui.button("use whole buffer").clicked() {
+                                self.set_selection(None);
+                            }
+                        });
+                    }
+                }
+                Mode::Insert => {
+                    ui.label("ctrl+enter: copy. escape: stop editing.");
+                }
+            }
+            if let Some(pending) = &self.pending {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label(format!("{}: reading files...", pending.label));
+                });
+            }
+            if let Some(error) = &self.error {
+                ui.label(
+                    egui::RichText::new(error)
+                        .monospace()
+                        .color(egui::Color32::RED),
+                );
+            }
+        });
+
+        if mode == Mode::Command {
+            // Check redo first, since consume_key ignores extra shift modifiers.
+            if ctx.input_mut(|i| i.consume_key(Modifiers::COMMAND | Modifiers::SHIFT, Key::Z)) {
+                self.redo();
+            } else if ctx.input_mut(|i| i.consume_key(Modifiers::COMMAND, Key::Z)) {
+                self.undo();
+            }
+        }
+
+        let copy = ctx.input(|i| match mode {
+            Mode::Command => pressed(i, Key::Enter),
+            Mode::Insert => i.modifiers.command && pressed(i, Key::Enter),
+        });
+        if copy {
+            copy_to_clipboard(&self.captured_clipboard);
+            closing = true;
+        }
+
+        for transform in transforms.iter() {
+            let Some(key) = transform.default_key().and_then(Key::from_name) else {
+                continue;
+            };
+            let pressed = ctx.input(|i| match mode {
+                Mode::Command => !i.modifiers.command && pressed(i, key),
+                Mode::Insert => i.modifiers.alt && pressed(i, key),
+            });
+            if pressed {
+                self.apply(transform);
+            }
+        }
+
+        closing
+    }
+
+    fn url_ui(&mut self, ctx: &egui::Context) -> bool {
+        let View::Url(parts) = &mut self.view else {
+            return false;
+        };
+
+        let mut rebuilt = None;
+        let mut apply = false;
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.strong("-- URL --");
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                rebuilt = views::url::show(ui, parts).ok();
+                ui.horizontal(|ui| {
+                    apply = ui.button("apply to buffer").clicked();
+                    ui.label("enter: copy url. escape: back to text.");
+                });
+            });
+        });
+
+        if apply {
+            if let Some(url) = rebuilt {
+                self.replace("explode url", url);
+                self.view = View::Text;
+            }
+            return false;
+        }
+
+        if ctx.input(|i| pressed(i, Key::Escape)) {
+            self.view = View::Text;
+        }
+
+        if ctx.input(|i| pressed(i, Key::Enter)) {
+            if let Some(url) = rebuilt {
+                copy_to_clipboard(&url);
+                return true;
+            }
+        }
+
+        false
+    }
+
+    fn query_ui(&mut self, ctx: &egui::Context) -> bool {
+        let View::Query(query) = &mut self.view else {
+            return false;
+        };
+
+        let mut apply = false;
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.strong("-- QUERY --");
+            ui.horizontal(|ui| {
+                apply = ui.button("apply to buffer").clicked();
+                ui.label("enter: copy. escape: back to text.");
+            });
+            query.show(ui, &self.captured_clipboard);
+        });
+
+        let result = query.result().map(str::to_string);
+        let copy_result = query.copy_result;
+        if apply {
+            if let Some(result) = result {
+                self.replace("query json", result);
+                self.view = View::Text;
+            }
+            return false;
+        }
+
+        if ctx.input(|i| pressed(i, Key::Escape)) {
+            self.view = View::Text;
+        }
+
+        if ctx.input(|i| pressed(i, Key::Enter)) {
+            if !copy_result {
+                copy_to_clipboard(&self.captured_clipboard);
+                return true;
+            }
+            if let Some(result) = result {
+                copy_to_clipboard(&result);
+                return true;
+            }
+        }
+
+        false
+    }
+
+    fn jwt_ui(&mut self, ctx: &egui::Context) -> bool {
+        let View::Jwt(view) = &mut self.view else {
+            return false;
+        };
+
+        let mut apply = false;
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.strong("-- JWT --");
+            ui.horizontal(|ui| {
+                apply = ui.button("apply to buffer").clicked();
+                ui.label("enter: copy header and payload. escape: back to text.");
+            });
+            view.show(ui);
+        });
+
+        let decoded = jwt::DecodeJwt.apply(&self.captured_clipboard);
+        if apply {
+            if let Ok(decoded) = decoded {
+                self.replace("decode jwt", decoded);
+                self.view = View::Text;
+            }
+            return false;
+        }
+
+        if ctx.input(|i| pressed(i, Key::Escape)) {
+            self.view = View::Text;
+        }
+
+        // Enter adds lines to a PEM key while its field is focused.
+        if !ctx.wants_keyboard_input() && ctx.input(|i| pressed(i, Key::Enter)) {
+            if let Ok(decoded) = decoded {
+                copy_to_clipboard(&decoded);
+                return true;
+            }
+        }
+
+        false
+    }
+
+    fn binary_ui(&mut self, ctx: &egui::Context) -> bool {
+        let View::Binary(view) = &self.view else {
+            return false;
+        };
+
+        let mut apply = None;
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.strong("-- BINARY --");
+            ui.horizontal(|ui| {
+                if ui.button("apply hex dump to buffer").clicked() {
+                    apply = Some(view.dump().to_string());
+                }
+                if ui.button("apply hex to buffer").clicked() {
+                    apply = Some(hex::encode(&view.bytes));
+                }
+                if ui.button("apply lossy text to buffer").clicked() {
+                    apply = Some(String::from_utf8_lossy(&view.bytes).into_owned());
+                }
+            });
+            ui.label("enter: copy hex dump. escape: back to text.");
+            view.show(ui);
+        });
+
+        if let Some(text) = apply {
+            let View::Binary(view) = std::mem::replace(&mut self.view, View::Text) else {
+                return false;
+            };
+            self.splice(&view.label, view.range, text);
+            return false;
+        }
+
+        if ctx.input(|i| pressed(i, Key::Escape)) {
+            self.view = View::Text;
+            return false;
+        }
+
+        if ctx.input(|i| pressed(i, Key::Enter)) {
+            copy_to_clipboard(view.dump());
+            return true;
+        }
+
+        false
+    }
+
+    fn unicode_ui(&mut self, ctx: &egui::Context, transforms: &Registry) -> bool {
+        let View::Unicode(view) = &mut self.view else {
+            return false;
+        };
+
+        let mut action = views::unicode::Action::None;
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.strong("-- CHARACTERS --");
+            ui.label("enter: copy. escape: back to text.");
+            if let Some(error) = &self.error {
+                ui.colored_label(egui::Color32::RED, error);
+            }
+            action = view.show(ui, &self.captured_clipboard);
+        });
+
+        match action {
+            views::unicode::Action::None => (),
+            views::unicode::Action::Run(name) => {
+                if let Some(transform) = transforms.get(name) {
+                    self.set_selection(None);
+                    self.apply(transform);
+                }
+            }
+            views::unicode::Action::Select(chars) => {
+                self.set_selection(Some(chars));
+                self.view = View::Text;
+                return false;
+            }
+        }
+
+        if ctx.input(|i| pressed(i, Key::Escape)) {
+            self.view = View::Text;
+        }
+
+        if ctx.input(|i| pressed(i, Key::Enter)) {
+            copy_to_clipboard(&self.captured_clipboard);
+            return true;
+        }
+
+        false
+    }
+
+    fn list_ui(&mut self, ctx: &egui::Context, transforms: &mut Registry) -> bool {
+        // Preview what applying would change: the selection, if there is one.
+        let selected = self.selected_bytes();
+        let input = match &selected {
+            Some(bytes) => &self.captured_clipboard[bytes.clone()],
+            None => self.captured_clipboard.as_str(),
+        };
+        let View::List(view) = &mut self.view else {
+            return false;
+        };
+        let Some(transform) = transforms.get_mut("join list") else {
+            self.view = View::Text;
+            return false;
+        };
+
+        let mut apply = false;
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.strong("-- LIST --");
+            ui.horizontal(|ui| {
+                apply = ui.button("apply to buffer").clicked();
+                ui.label("enter: copy. escape: back to text.");
+            });
+            if let Some(selection) = &self.selection {
+                ui.label(format!(
+                    "joining the {} selected characters only.",
+                    selection.len()
+                ));
+            }
+            view.show(ui, transform, input);
+        });
+
+        let result = view.result().map(str::to_string);
+        if apply {
+            self.apply(transform);
+            self.view = View::Text;
+            return false;
+        }
+
+        if ctx.input(|i| pressed(i, Key::Escape)) {
+            self.view = View::Text;
+        }
+
+        if ctx.input(|i| pressed(i, Key::Enter)) {
+            if let Some(result) = result {
+                copy_to_clipboard(&result);
+                return true;
+            }
+        }
+
+        false
+    }
+}
+
+fn copy_to_clipboard(text: &str) {
+    let mut clipboard = Clipboard::new().unwrap();
+    clipboard.set_text(text).unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escape_leaves_insert_mode_then_closes() {
+        assert_eq!(Mode::Insert.next(false, true), Some(Mode::Command));
+        assert_eq!(Mode::Command.next(false, true), None);
+    }
+
+    #[test]
+    fn focus_decides_the_mode() {
+        assert_eq!(Mode::Command.next(true, false), Some(Mode::Insert));
+        assert_eq!(Mode::Insert.next(true, false), Some(Mode::Insert));
+        assert_eq!(Mode::Insert.next(false, false), Some(Mode::Command));
+        assert_eq!(Mode::Command.next(false, false), Some(Mode::Command));
+    }
+
+    #[test]
+    fn key_names_ignore_case() {
+        assert_eq!(key_named("b"), Some(Key::B));
+        assert_eq!(key_named("space"), Some(Key::Space));
+        assert_eq!(key_named("HOME"), Some(Key::Home));
+        assert_eq!(key_named("Esc"), Some(Key::Escape));
+        assert_eq!(key_named("nope"), None);
+        assert_eq!(key_named(""), None);
+    }
+
+    #[test]
+    fn keys_act_once_on_the_press() {
+        let key = |pressed, repeat| egui::Event::Key {
+            key: Key::B,
+            physical_key: None,
+            pressed,
+            repeat,
+            modifiers: Modifiers::NONE,
+        };
+        let input = |events| {
+            let mut input = egui::InputState::default();
+            input.events = events;
+            input
+        };
+        assert!(pressed(&input(vec![key(true, false)]), Key::B));
+        assert!(!pressed(&input(vec![key(true, false)]), Key::V));
+        assert!(!pressed(&input(vec![key(true, true)]), Key::B));
+        assert!(!pressed(&input(vec![key(false, false)]), Key::B));
+    }
+}
@@ -0,0 +1,45 @@
+use std::ops::Range;
+
+use backflip_transforms::hex;
+use eframe::egui;
+
+/// Bytes a transform decoded that aren't UTF-8, so they can't go in the buffer as they are.
+pub struct BinaryView {
+    /// The transform that produced the bytes, for the history.
+    pub label: String,
+    pub bytes: Vec<u8>,
+    /// Byte offsets of the selection the bytes were decoded from, if there was one.
+    pub range: Option<Range<usize>>,
+    dump: String,
+}
+
+impl BinaryView {
+    pub fn new(label: &str, bytes: Vec<u8>, range: Option<Range<usize>>) -> Self {
+        BinaryView {
+            label: label.to_string(),
+            dump: hex::dump(&bytes),
+            bytes,
+            range,
+        }
+    }
+
+    pub fn dump(&self) -> &str {
+        &self.dump
+    }
+
+    pub fn show(&self, ui: &mut egui::Ui) {
+        let invalid = std::str::from_utf8(&self.bytes)
+            .err()
+            .map_or(0, |e| e.valid_up_to());
+        ui.label(format!(
+            "{} bytes from {}, which aren't utf-8 from offset {:#x}.",
+            self.bytes.len(),
+            self.label,
+            invalid
+        ));
+        ui.separator();
+        egui::ScrollArea::both().show(ui, |ui| {
+            ui.add(egui::Label::new(egui::RichText::new(&self.dump).monospace()).wrap(false));
+        });
+    }
+}
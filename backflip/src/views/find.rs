@@ -0,0 +1,167 @@
+use std::sync::Arc;
+
+use backflip_transforms::replace::Rule;
+use eframe::egui::{
+    self,
+    text::{LayoutJob, TextFormat},
+    Galley,
+};
+use regex::Regex;
+
+pub enum Action {
+    None,
+    ReplaceAll,
+    Save,
+    Close,
+}
+
+pub struct FindBar {
+    pub pattern: String,
+    pub replacement: String,
+    /// What to call the pair when it's saved as a transform, and the key to bind it to.
+    pub name: String,
+    pub key: String,
+    /// Why saving failed.
+    pub error: Option<String>,
+    /// `pattern` compiled, or why it didn't compile. `None` while the pattern is empty.
+    regex: Option<Result<Regex, String>>,
+    compiled: String,
+    focus_requested: bool,
+}
+
+impl FindBar {
+    pub fn new() -> Self {
+        FindBar {
+            pattern: String::new(),
+            replacement: String::new(),
+            name: String::new(),
+            key: String::new(),
+            error: None,
+            regex: None,
+            compiled: String::new(),
+            focus_requested: true,
+        }
+    }
+
+    pub fn focus(&mut self) {
+        self.focus_requested = true;
+    }
+
+    pub fn regex(&self) -> Option<&Regex> {
+        self.regex.as_ref()?.as_ref().ok()
+    }
+
+    pub fn rule(&self) -> anyhow::Result<Rule> {
+        Rule::new(&self.pattern, &self.replacement)
+    }
+
+    /// Draws the bar, counting matches of the pattern in `input`.
+    pub fn show(&mut self, ui: &mut egui::Ui, input: &str) -> Action {
+        if self.compiled != self.pattern {
+            self.regex = (!self.pattern.is_empty())
+                .then(|| Regex::new(&self.pattern).map_err(|e| e.to_string()));
+            self.compiled = self.pattern.clone();
+        }
+
+        let mut action = Action::None;
+        egui::Grid::new("find").num_columns(2).show(ui, |ui| {
+            ui.label("find");
+            ui.horizontal(|ui| {
+                let field = ui.add(
+                    egui::TextEdit::singleline(&mut self.pattern)
+                        .hint_text(r"(?<key>\w+)=(?<value>\w+)")
+                        .code_editor(),
+                );
+                if self.focus_requested {
+                    self.focus_requested = false;
+                    field.request_focus();
+                }
+                // Enter would normally drop focus, and then copy the buffer once released.
+                if field.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                    action = Action::ReplaceAll;
+                    field.request_focus();
+                }
+                match &self.regex {
+                    Some(Ok(regex)) => {
+                        ui.label(format!("{} matches", regex.find_iter(input).count()));
+                    }
+                    Some(Err(e)) => {
+                        ui.colored_label(egui::Color32::RED, e.lines().last().unwrap_or(e));
+                    }
+                    None => (),
+                }
+                if ui.button("close").clicked() {
+                    action = Action::Close;
+                }
+            });
+            ui.end_row();
+
+            ui.label("replace");
+            ui.horizontal(|ui| {
+                let field = ui.add(
+                    egui::TextEdit::singleline(&mut self.replacement)
+                        .hint_text("$value=$key")
+                        .code_editor(),
+                );
+                if field.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                    action = Action::ReplaceAll;
+                    field.request_focus();
+                }
+                if ui.button("replace all").clicked() {
+                    action = Action::ReplaceAll;
+                }
+            });
+            ui.end_row();
+
+            ui.label("save as");
+            ui.horizontal(|ui| {
+                ui.add(egui::TextEdit::singleline(&mut self.name).hint_text("name"));
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.key)
+                        .hint_text("key")
+                        .desired_width(40.0),
+                );
+                let save = ui
+                    .button("save")
+                    .on_hover_text("saved transforms last until backflip quits");
+                if save.clicked() {
+                    action = Action::Save;
+                }
+                if let Some(error) = &self.error {
+                    ui.colored_label(egui::Color32::RED, error);
+                }
+            });
+            ui.end_row();
+        });
+        action
+    }
+}
+
+/// Lays out the editor's text with every match of `regex` highlighted, or plainly if the
+/// pattern is empty or doesn't compile.
+pub fn highlight(ui: &egui::Ui, text: &str, regex: Option<&Regex>, wrap_width: f32) -> Arc<Galley> {
+    let visuals = ui.visuals();
+    let plain = TextFormat::simple(
+        egui::TextStyle::Body.resolve(ui.style()),
+        visuals
+            .override_text_color
+            .unwrap_or_else(|| visuals.widgets.inactive.text_color()),
+    );
+    let found = TextFormat {
+        background: visuals.warn_fg_color.gamma_multiply(0.4),
+        ..plain.clone()
+    };
+
+    let mut job = LayoutJob::default();
+    let mut last = 0;
+    if let Some(regex) = regex {
+        for m in regex.find_iter(text) {
+            job.append(&text[last..m.start()], 0.0, plain.clone());
+            job.append(m.as_str(), 0.0, found.clone());
+            last = m.end();
+        }
+    }
+    job.append(&text[last..], 0.0, plain);
+    job.wrap.max_width = wrap_width;
+    ui.fonts(|f| f.layout_job(job))
+}
@@ -0,0 +1,54 @@
+use backflip_transforms::integer::{self, Base};
+use eframe::egui;
+
+fn bytes(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Draws `value` in each base, and how it's stored as an 8, 16, 32 and 64-bit integer.
+pub fn show(ui: &mut egui::Ui, value: i128) {
+    egui::Grid::new("bases").num_columns(2).show(ui, |ui| {
+        let bases = [
+            (Base::Decimal, "dec"),
+            (Base::Hex, "hex"),
+            (Base::Octal, "oct"),
+            (Base::Binary, "bin"),
+        ];
+        for (base, name) in bases {
+            ui.label(name);
+            ui.monospace(integer::to_base(value, base as usize, true));
+            ui.end_row();
+        }
+    });
+    for representation in integer::representations(value) {
+        ui.separator();
+        if representation.fits {
+            ui.strong(format!("{}-bit", representation.bits));
+        } else {
+            ui.colored_label(
+                egui::Color32::YELLOW,
+                format!("{}-bit, truncated", representation.bits),
+            );
+        }
+        egui::Grid::new(("representation", representation.bits))
+            .num_columns(2)
+            .show(ui, |ui| {
+                ui.label(format!("u{}", representation.bits));
+                ui.monospace(representation.unsigned.to_string());
+                ui.end_row();
+                ui.label(format!("i{}", representation.bits));
+                ui.monospace(representation.signed.to_string());
+                ui.end_row();
+                ui.label("le");
+                ui.monospace(bytes(&representation.little_endian));
+                ui.end_row();
+                ui.label("be");
+                ui.monospace(bytes(&representation.big_endian));
+                ui.end_row();
+            });
+    }
+}
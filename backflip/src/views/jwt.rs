@@ -0,0 +1,90 @@
+use backflip_transforms::jwt::{self, Token, Validity};
+use chrono::{Local, Utc};
+use eframe::egui;
+
+pub struct JwtView {
+    pub token: Token,
+    /// Secret or PEM public key to check the signature with.
+    pub key: String,
+    /// The result of checking the signature with `checked_key`, so it's only rechecked when
+    /// `key` changes.
+    verified: Option<Result<bool, String>>,
+    checked_key: String,
+}
+
+impl JwtView {
+    pub fn new(token: Token) -> Self {
+        JwtView {
+            token,
+            key: String::new(),
+            verified: None,
+            checked_key: String::new(),
+        }
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui) {
+        let now = Utc::now();
+        match self.token.validity(now) {
+            Validity::Valid => ui.colored_label(egui::Color32::LIGHT_GREEN, "valid"),
+            Validity::Expired => ui.colored_label(egui::Color32::RED, "expired"),
+            Validity::NotYetValid => ui.colored_label(egui::Color32::YELLOW, "not valid yet"),
+        };
+        egui::Grid::new("jwt times").num_columns(3).show(ui, |ui| {
+            for time in self.token.times() {
+                ui.label(format!("{} ({})", time.label, time.claim));
+                ui.monospace(
+                    time.date
+                        .with_timezone(&Local)
+                        .format("%Y-%m-%d %H:%M:%S %:z")
+                        .to_string(),
+                );
+                ui.label(jwt::relative(time.date, now));
+                ui.end_row();
+            }
+        });
+
+        ui.separator();
+        ui.horizontal(|ui| {
+            ui.label(match self.token.algorithm() {
+                Some(algorithm) if algorithm.starts_with("HS") => "secret",
+                _ => "public key",
+            });
+            ui.add(
+                egui::TextEdit::multiline(&mut self.key)
+                    .hint_text("-----BEGIN PUBLIC KEY-----")
+                    .desired_rows(1)
+                    .code_editor(),
+            );
+        });
+        if self.key != self.checked_key {
+            self.verified = (!self.key.is_empty())
+                .then(|| self.token.verify(&self.key).map_err(|e| e.to_string()));
+            self.checked_key = self.key.clone();
+        }
+        match &self.verified {
+            Some(Ok(true)) => {
+                ui.colored_label(egui::Color32::LIGHT_GREEN, "signature verified");
+            }
+            Some(Ok(false)) => {
+                ui.colored_label(egui::Color32::RED, "signature doesn't match");
+            }
+            Some(Err(e)) => {
+                ui.colored_label(egui::Color32::RED, e);
+            }
+            None => {
+                ui.label("signature not checked");
+            }
+        }
+
+        ui.separator();
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            for (label, value) in [
+                ("header", &self.token.header),
+                ("payload", &self.token.payload),
+            ] {
+                ui.strong(label);
+                ui.monospace(serde_json::to_string_pretty(value).unwrap_or_default());
+            }
+        });
+    }
+}
@@ -0,0 +1,44 @@
+use backflip_transforms::Transform;
+use eframe::egui;
+
+use crate::views;
+
+/// A list transform's options, with the output shown live as they change.
+pub struct ListView {
+    /// The input `result` was made from, or `None` before the first frame.
+    input: Option<String>,
+    result: Result<String, String>,
+}
+
+impl ListView {
+    pub fn new() -> Self {
+        ListView {
+            input: None,
+            result: Ok(String::new()),
+        }
+    }
+
+    pub fn result(&self) -> Option<&str> {
+        self.result.as_deref().ok()
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui, transform: &mut dyn Transform, input: &str) {
+        let name = transform.name().to_string();
+        let changed = views::settings::show(ui, &name, transform.settings());
+        ui.label("\\n and \\t in the delimiter and separator stand for a newline and a tab.");
+        if changed || self.input.as_deref() != Some(input) {
+            self.result = transform.apply(input).map_err(|e| e.to_string());
+            self.input = Some(input.to_string());
+        }
+
+        ui.separator();
+        egui::ScrollArea::vertical().show(ui, |ui| match &self.result {
+            Ok(result) => {
+                ui.monospace(result);
+            }
+            Err(e) => {
+                ui.label(egui::RichText::new(e).monospace().color(egui::Color32::RED));
+            }
+        });
+    }
+}
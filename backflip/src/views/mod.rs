@@ -0,0 +1,9 @@
+pub mod binary;
+pub mod find;
+pub mod integer;
+pub mod jwt;
+pub mod list;
+pub mod query;
+pub mod settings;
+pub mod unicode;
+pub mod url;
@@ -0,0 +1,71 @@
+use backflip_transforms::query;
+use eframe::egui;
+
+pub struct QueryView {
+    pub query: String,
+    /// Print strings without quotes, like `jq -r`.
+    pub raw: bool,
+    /// Whether enter copies the query result rather than the original buffer.
+    pub copy_result: bool,
+    result: Result<String, String>,
+    /// The query and `raw` flag `result` was computed from, so it's only rerun on change.
+    evaluated: Option<(String, bool)>,
+    focus_requested: bool,
+}
+
+impl QueryView {
+    pub fn new() -> Self {
+        QueryView {
+            query: ".".to_string(),
+            raw: false,
+            copy_result: true,
+            result: Ok(String::new()),
+            evaluated: None,
+            focus_requested: true,
+        }
+    }
+
+    pub fn result(&self) -> Option<&str> {
+        self.result.as_deref().ok()
+    }
+
+    /// Draws the query box with the live result of running it against `input` beneath.
+    pub fn show(&mut self, ui: &mut egui::Ui, input: &str) {
+        ui.horizontal(|ui| {
+            ui.label("query");
+            let field = ui.add(
+                egui::TextEdit::singleline(&mut self.query)
+                    .hint_text(".items[].id")
+                    .code_editor(),
+            );
+            if self.focus_requested {
+                self.focus_requested = false;
+                field.request_focus();
+            }
+            ui.checkbox(&mut self.raw, "raw strings");
+        });
+        ui.horizontal(|ui| {
+            ui.label("enter copies");
+            ui.radio_value(&mut self.copy_result, true, "result");
+            ui.radio_value(&mut self.copy_result, false, "original");
+        });
+
+        let current = (self.query.clone(), self.raw);
+        if self.evaluated.as_ref() != Some(&current) {
+            self.result = query::run(&self.query, input)
+                .and_then(|values| query::format(&values, self.raw))
+                .map_err(|e| e.to_string());
+            self.evaluated = Some(current);
+        }
+
+        ui.separator();
+        egui::ScrollArea::vertical().show(ui, |ui| match &self.result {
+            Ok(result) => {
+                ui.monospace(result);
+            }
+            Err(e) => {
+                ui.label(egui::RichText::new(e).monospace().color(egui::Color32::RED));
+            }
+        });
+    }
+}
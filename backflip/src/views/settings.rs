@@ -0,0 +1,47 @@
+use backflip_transforms::Setting;
+use eframe::egui;
+
+/// Draws a widget for each of a transform's settings. `id_source` keeps widgets belonging to
+/// different transforms apart. Returns whether any of them changed.
+pub fn show(ui: &mut egui::Ui, id_source: &str, settings: Vec<Setting>) -> bool {
+    let mut changed = false;
+    for setting in settings {
+        match setting {
+            Setting::Number {
+                label,
+                value,
+                range,
+            } => {
+                ui.horizontal(|ui| {
+                    changed |= ui
+                        .add(egui::DragValue::new(value).clamp_range(range))
+                        .changed();
+                    ui.label(label);
+                });
+            }
+            Setting::Toggle { label, value } => {
+                changed |= ui.checkbox(value, label).changed();
+            }
+            Setting::Choice {
+                label,
+                value,
+                options,
+            } => {
+                egui::ComboBox::from_id_source((id_source, label))
+                    .selected_text(options[*value])
+                    .show_ui(ui, |ui| {
+                        for (index, option) in options.iter().enumerate() {
+                            changed |= ui.selectable_value(value, index, *option).changed();
+                        }
+                    });
+            }
+            Setting::Text { label, value } => {
+                ui.horizontal(|ui| {
+                    ui.label(label);
+                    changed |= ui.text_edit_singleline(value).changed();
+                });
+            }
+        }
+    }
+    changed
+}
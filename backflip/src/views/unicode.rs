@@ -0,0 +1,112 @@
+use std::ops::Range;
+
+use backflip_transforms::unicode::{self, CharInfo};
+use eframe::egui;
+
+pub enum Action {
+    None,
+    /// Run the transform with this name on the buffer.
+    Run(&'static str),
+    /// Select these characters in the editor.
+    Select(Range<usize>),
+}
+
+/// Transforms offered as buttons above the table.
+const TRANSFORMS: &[&str] = &["strip invisibles", "nfc normalize", "nfkc normalize"];
+
+pub struct UnicodeView {
+    /// Only list the characters `unicode::invisible` flags.
+    pub only_invisible: bool,
+    chars: Vec<CharInfo>,
+    /// The text `chars` describes, so it's only recomputed when the buffer changes.
+    inspected: Option<String>,
+}
+
+impl UnicodeView {
+    pub fn new() -> Self {
+        UnicodeView {
+            only_invisible: false,
+            chars: vec![],
+            inspected: None,
+        }
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui, input: &str) -> Action {
+        if self.inspected.as_deref() != Some(input) {
+            self.chars = unicode::inspect(input);
+            self.inspected = Some(input.to_string());
+        }
+
+        let mut action = Action::None;
+        let invisible = self.chars.iter().filter(|c| c.invisible.is_some()).count();
+        ui.horizontal(|ui| {
+            ui.label(format!(
+                "{} characters, {} bytes.",
+                self.chars.len(),
+                input.len()
+            ));
+            if invisible > 0 {
+                ui.colored_label(egui::Color32::YELLOW, format!("{} invisible.", invisible));
+            }
+            ui.checkbox(&mut self.only_invisible, "only invisible");
+        });
+        ui.horizontal(|ui| {
+            for name in TRANSFORMS {
+                if ui.button(*name).clicked() {
+                    action = Action::Run(name);
+                }
+            }
+        });
+        ui.separator();
+        ui.monospace(format!(
+            "{:>7}  {:<2}  {:<6}  {:<11}  {}  {}",
+            "offset", "", "code", "utf-8", "gc", "name"
+        ));
+
+        // Indices into `chars` (which are also character offsets into the buffer).
+        let rows: Vec<usize> = (0..self.chars.len())
+            .filter(|&i| !self.only_invisible || self.chars[i].invisible.is_some())
+            .collect();
+        let row_height = ui.text_style_height(&egui::TextStyle::Monospace);
+        egui::ScrollArea::both().auto_shrink([false; 2]).show_rows(
+            ui,
+            row_height,
+            rows.len(),
+            |ui, range| {
+                for &index in &rows[range] {
+                    let info = &self.chars[index];
+                    let glyph = match info.invisible {
+                        Some(_) => "·".to_string(),
+                        None if info.c.is_control() => " ".to_string(),
+                        None => info.c.to_string(),
+                    };
+                    let mut text = format!(
+                        "{:>7}  {:<2}  U+{:04X}  {:<11}  {}  {}",
+                        info.offset, glyph, info.c as u32, info.utf8, info.category, info.name
+                    );
+                    if let Some(kind) = info.invisible {
+                        text.push_str(&format!("  ({})", kind.name()));
+                    }
+                    let mut label = egui::RichText::new(text).monospace();
+                    if info.invisible.is_some() {
+                        label = label.color(egui::Color32::YELLOW);
+                    }
+                    let response = ui
+                        .add(
+                            egui::Label::new(label)
+                                .wrap(false)
+                                .sense(egui::Sense::click()),
+                        )
+                        .on_hover_text(format!(
+                            "{}. click to select it.",
+                            unicode::category_name(info.category)
+                        ));
+                    if response.clicked() {
+                        action = Action::Select(index..index + 1);
+                    }
+                }
+            },
+        );
+        action
+    }
+}
@@ -0,0 +1,60 @@
+use backflip_transforms::url::UrlParts;
+use eframe::egui;
+
+/// Draws `parts` as an editable table, followed by the URL rebuilt from it. Returns the rebuilt
+/// URL, or why it couldn't be built.
+pub fn show(ui: &mut egui::Ui, parts: &mut UrlParts) -> anyhow::Result<String> {
+    egui::Grid::new("url parts").num_columns(2).show(ui, |ui| {
+        for (label, value) in [
+            ("scheme", &mut parts.scheme),
+            ("username", &mut parts.username),
+            ("password", &mut parts.password),
+            ("host", &mut parts.host),
+            ("port", &mut parts.port),
+            ("path", &mut parts.path),
+            ("fragment", &mut parts.fragment),
+        ] {
+            ui.label(label);
+            ui.text_edit_singleline(value);
+            ui.end_row();
+        }
+    });
+
+    ui.separator();
+    let mut remove = None;
+    egui::Grid::new("url query").num_columns(3).show(ui, |ui| {
+        ui.strong("parameter");
+        ui.strong("value");
+        ui.end_row();
+        for (index, (key, value)) in parts.query.iter_mut().enumerate() {
+            ui.text_edit_singleline(key);
+            if let Some(value) = value {
+                ui.text_edit_singleline(value);
+            } else if ui
+                .button("add value")
+                .on_hover_text("the parameter has no = and no value")
+                .clicked()
+            {
+                *value = Some(String::new());
+            }
+            if ui.small_button("remove").clicked() {
+                remove = Some(index);
+            }
+            ui.end_row();
+        }
+    });
+    if let Some(index) = remove {
+        parts.query.remove(index);
+    }
+    if ui.button("add parameter").clicked() {
+        parts.query.push((String::new(), Some(String::new())));
+    }
+
+    ui.separator();
+    let rebuilt = parts.to_url();
+    match &rebuilt {
+        Ok(url) => ui.monospace(url),
+        Err(e) => ui.colored_label(egui::Color32::RED, e.to_string()),
+    };
+    rebuilt
+}
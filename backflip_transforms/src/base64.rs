@@ -0,0 +1,152 @@
+use ::base64::{
+    alphabet,
+    engine::{
+        general_purpose::{GeneralPurpose, GeneralPurposeConfig, STANDARD, STANDARD_NO_PAD},
+        general_purpose::{URL_SAFE, URL_SAFE_NO_PAD},
+        DecodePaddingMode,
+    },
+    Engine,
+};
+
+use crate::{hex, Transform};
+
+#[derive(Clone, Copy)]
+pub enum Variant {
+    Standard,
+    StandardNoPad,
+    UrlSafe,
+    UrlSafeNoPad,
+}
+
+/// Encodes the buffer's UTF-8 bytes as base64.
+pub struct Base64Encode(pub Variant);
+
+impl Transform for Base64Encode {
+    fn name(&self) -> &str {
+        match self.0 {
+            Variant::Standard => "base64 encode",
+            Variant::StandardNoPad => "base64 encode (unpadded)",
+            Variant::UrlSafe => "base64url encode",
+            Variant::UrlSafeNoPad => "base64url encode (unpadded)",
+        }
+    }
+
+    fn description(&self) -> &str {
+        match self.0 {
+            Variant::Standard => "encode as base64",
+            Variant::StandardNoPad => "encode as base64 without = padding",
+            Variant::UrlSafe => "encode as url-safe base64",
+            Variant::UrlSafeNoPad => "encode as url-safe base64 without = padding",
+        }
+    }
+
+    fn default_key(&self) -> Option<&str> {
+        match self.0 {
+            Variant::Standard => Some("B"),
+            _ => None,
+        }
+    }
+
+    fn apply(&self, input: &str) -> anyhow::Result<String> {
+        let engine = match self.0 {
+            Variant::Standard => STANDARD,
+            Variant::StandardNoPad => STANDARD_NO_PAD,
+            Variant::UrlSafe => URL_SAFE,
+            Variant::UrlSafeNoPad => URL_SAFE_NO_PAD,
+        };
+        Ok(engine.encode(input))
+    }
+}
+
+/// Decodes any of the base64 variants, working out which alphabet was used from the input.
+/// Bytes that aren't UTF-8 come back as a hex dump.
+pub struct Base64Decode;
+
+impl Transform for Base64Decode {
+    fn name(&self) -> &str {
+        "base64 decode"
+    }
+
+    fn description(&self) -> &str {
+        "decode base64 or base64url, padded or not"
+    }
+
+    fn default_key(&self) -> Option<&str> {
+        Some("V")
+    }
+
+    fn apply(&self, input: &str) -> anyhow::Result<String> {
+        Ok(hex::text_or_dump(decode(input)?))
+    }
+
+    fn apply_bytes(&self, input: &str) -> Option<anyhow::Result<Vec<u8>>> {
+        Some(decode(input))
+    }
+}
+
+/// Decodes base64 in either alphabet, with or without padding. Whitespace is ignored so that
+/// wrapped blobs pasted out of logs or PEM files decode too.
+pub fn decode(input: &str) -> anyhow::Result<Vec<u8>> {
+    let input: String = input.chars().filter(|c| !c.is_whitespace()).collect();
+    let url_safe = input.contains(['-', '_']);
+    if url_safe && input.contains(['+', '/']) {
+        anyhow::bail!("input mixes the standard and url-safe alphabets");
+    }
+    let alphabet = if url_safe {
+        &alphabet::URL_SAFE
+    } else {
+        &alphabet::STANDARD
+    };
+    let config =
+        GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent);
+    Ok(GeneralPurpose::new(alphabet, config).decode(input)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_variant_round_trips() {
+        let text = "subjects?_d>>~ üñí";
+        for variant in [
+            Variant::Standard,
+            Variant::StandardNoPad,
+            Variant::UrlSafe,
+            Variant::UrlSafeNoPad,
+        ] {
+            let encoded = Base64Encode(variant).apply(text).unwrap();
+            assert_eq!(Base64Decode.apply(&encoded).unwrap(), text);
+        }
+    }
+
+    #[test]
+    fn encodes_each_alphabet() {
+        assert_eq!(Base64Encode(Variant::Standard).apply("?>").unwrap(), "Pz4=");
+        assert_eq!(Base64Encode(Variant::UrlSafe).apply("??>").unwrap(), "Pz8-");
+        assert_eq!(
+            Base64Encode(Variant::UrlSafeNoPad).apply("?>").unwrap(),
+            "Pz4"
+        );
+    }
+
+    #[test]
+    fn decode_ignores_whitespace() {
+        assert_eq!(decode("aGVs\r\nbG8=\n").unwrap(), b"hello");
+    }
+
+    #[test]
+    fn decode_rejects_mixed_alphabets() {
+        assert!(decode("ab+-").is_err());
+        assert!(decode("not base64!").is_err());
+    }
+
+    #[test]
+    fn binary_decodes_to_a_dump() {
+        assert_eq!(
+            Base64Decode.apply_bytes("/wA=").unwrap().unwrap(),
+            [0xff, 0]
+        );
+        assert!(Base64Decode.apply("/wA=").unwrap().starts_with("00000000"));
+    }
+}
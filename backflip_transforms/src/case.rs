@@ -0,0 +1,323 @@
+use crate::{Setting, Transform};
+
+/// Splits an identifier into words at separators and case changes. A run of capitals is kept
+/// together as an acronym, so `HTTPServer` is `HTTP` + `Server` and `userID` is `user` + `ID`.
+/// Digits stay attached to the word before them.
+pub fn words(s: &str) -> Vec<String> {
+    let chars: Vec<char> = s.chars().collect();
+    let mut words = vec![];
+    let mut word = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        if !c.is_alphanumeric() {
+            if !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+            continue;
+        }
+        if let Some(&prev) = word.chars().last().as_ref() {
+            let next = chars.get(i + 1).copied();
+            let boundary = c.is_uppercase()
+                && (prev.is_lowercase()
+                    || prev.is_ascii_digit()
+                    || (prev.is_uppercase() && next.is_some_and(|n| n.is_lowercase())));
+            if boundary {
+                words.push(std::mem::take(&mut word));
+            }
+        }
+        word.push(c);
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first
+            .to_uppercase()
+            .chain(chars.flat_map(char::to_lowercase))
+            .collect(),
+        None => String::new(),
+    }
+}
+
+pub fn to_snake(s: &str) -> String {
+    join_lowercase(&words(s), "_")
+}
+
+pub fn to_pascal(s: &str) -> String {
+    words(s).iter().map(|w| capitalize(w)).collect()
+}
+
+pub fn to_camel(s: &str) -> String {
+    words(s)
+        .iter()
+        .enumerate()
+        .map(|(i, w)| {
+            if i == 0 {
+                w.to_lowercase()
+            } else {
+                capitalize(w)
+            }
+        })
+        .collect()
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Case {
+    Snake,
+    Camel,
+    Pascal,
+    Kebab,
+    ScreamingSnake,
+    Title,
+}
+
+impl Case {
+    pub fn convert(self, s: &str) -> String {
+        let words = words(s);
+        match self {
+            Case::Snake => to_snake(s),
+            Case::Camel => to_camel(s),
+            Case::Pascal => to_pascal(s),
+            Case::Kebab => join_lowercase(&words, "-"),
+            Case::ScreamingSnake => words
+                .iter()
+                .map(|w| w.to_uppercase())
+                .collect::<Vec<_>>()
+                .join("_"),
+            Case::Title => words
+                .iter()
+                .map(|w| capitalize(w))
+                .collect::<Vec<_>>()
+                .join(" "),
+        }
+    }
+}
+
+fn join_lowercase(words: &[String], separator: &str) -> String {
+    words
+        .iter()
+        .map(|w| w.to_lowercase())
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+/// Converts an identifier, keeping any leading and trailing underscores (`__init__`, `_private`)
+/// since they usually mean something.
+fn convert_identifier(token: &str, case: Case) -> String {
+    let core = token.trim_matches('_');
+    if core.is_empty() {
+        return token.to_string();
+    }
+    let start = token.len() - token.trim_start_matches('_').len();
+    let end = start + core.len();
+    format!("{}{}{}", &token[..start], case.convert(core), &token[end..])
+}
+
+/// Calls `f` on every identifier-like token in `input`, leaving everything else alone. Tokens
+/// starting with a digit are numbers, like `0x1F` or `1e9`, so they're skipped. With `hyphens`,
+/// a hyphen between two letters is part of the token, so `foo-bar` is one token but `len-1` and
+/// `a - b` are still subtractions.
+fn map_identifiers(input: &str, hyphens: bool, mut f: impl FnMut(&str) -> String) -> String {
+    let chars: Vec<(usize, char)> = input.char_indices().collect();
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+    let mut out = String::with_capacity(input.len());
+    let mut i = 0;
+    while i < chars.len() {
+        let (start, c) = chars[i];
+        if !is_word(c) {
+            out.push(c);
+            i += 1;
+            continue;
+        }
+        let mut j = i + 1;
+        while j < chars.len() {
+            let c = chars[j].1;
+            let joins_hyphen = hyphens
+                && c == '-'
+                && chars[j - 1].1.is_alphabetic()
+                && chars.get(j + 1).is_some_and(|&(_, n)| n.is_alphabetic());
+            if !is_word(c) && !joins_hyphen {
+                break;
+            }
+            j += 1;
+        }
+        let end = chars.get(j).map_or(input.len(), |&(end, _)| end);
+        let token = &input[start..end];
+        if c.is_ascii_digit() {
+            out.push_str(token);
+        } else {
+            out.push_str(&f(token));
+        }
+        i = j;
+    }
+    out
+}
+
+/// Converts identifiers between naming conventions, either token by token or treating each
+/// line as one name made of words.
+pub struct ConvertCase {
+    pub case: Case,
+    pub per_line: bool,
+    /// Read `foo-bar` as one kebab-case name rather than a subtraction.
+    pub hyphens: bool,
+}
+
+impl ConvertCase {
+    pub fn new(case: Case) -> Self {
+        ConvertCase {
+            case,
+            per_line: false,
+            hyphens: false,
+        }
+    }
+}
+
+impl Transform for ConvertCase {
+    fn name(&self) -> &str {
+        match self.case {
+            Case::Snake => "snake case",
+            Case::Camel => "camel case",
+            Case::Pascal => "pascal case",
+            Case::Kebab => "kebab case",
+            Case::ScreamingSnake => "screaming snake case",
+            Case::Title => "title case",
+        }
+    }
+
+    fn description(&self) -> &str {
+        match self.case {
+            Case::Snake => "convert identifiers to snake_case",
+            Case::Camel => "convert identifiers to camelCase",
+            Case::Pascal => "convert identifiers to PascalCase",
+            Case::Kebab => "convert identifiers to kebab-case",
+            Case::ScreamingSnake => "convert identifiers to SCREAMING_SNAKE_CASE",
+            Case::Title => "convert identifiers to Title Case",
+        }
+    }
+
+    fn default_key(&self) -> Option<&str> {
+        Some(match self.case {
+            Case::Snake => "N",
+            Case::Camel => "C",
+            Case::Pascal => "E",
+            Case::Kebab => "K",
+            Case::ScreamingSnake => "H",
+            Case::Title => "T",
+        })
+    }
+
+    fn apply(&self, input: &str) -> anyhow::Result<String> {
+        if !self.per_line {
+            return Ok(map_identifiers(input, self.hyphens, |token| {
+                convert_identifier(token, self.case)
+            }));
+        }
+        let lines: Vec<String> = input
+            .split('\n')
+            .map(|line| {
+                let trimmed = line.trim();
+                if trimmed.is_empty() {
+                    return line.to_string();
+                }
+                // Keep the indentation, and the \r of a CRLF line ending.
+                let start = line.len() - line.trim_start().len();
+                let end = start + trimmed.len();
+                format!(
+                    "{}{}{}",
+                    &line[..start],
+                    self.case.convert(trimmed),
+                    &line[end..]
+                )
+            })
+            .collect();
+        Ok(lines.join("\n"))
+    }
+
+    fn settings(&mut self) -> Vec<Setting<'_>> {
+        vec![
+            Setting::Toggle {
+                label: "whole lines",
+                value: &mut self.per_line,
+            },
+            Setting::Toggle {
+                label: "hyphens join words",
+                value: &mut self.hyphens,
+            },
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_words() {
+        assert_eq!(words("HTTPServer"), ["HTTP", "Server"]);
+        assert_eq!(words("userID"), ["user", "ID"]);
+        assert_eq!(words("utf8Decode"), ["utf8", "Decode"]);
+        assert_eq!(words("__foo--bar  baz"), ["foo", "bar", "baz"]);
+        assert!(words("").is_empty());
+    }
+
+    #[test]
+    fn converts_between_cases() {
+        let name = "parseHTTPResponse";
+        assert_eq!(Case::Snake.convert(name), "parse_http_response");
+        assert_eq!(Case::Camel.convert(name), "parseHttpResponse");
+        assert_eq!(Case::Pascal.convert(name), "ParseHttpResponse");
+        assert_eq!(Case::Kebab.convert(name), "parse-http-response");
+        assert_eq!(Case::ScreamingSnake.convert(name), "PARSE_HTTP_RESPONSE");
+        assert_eq!(Case::Title.convert(name), "Parse Http Response");
+        for case in [Case::Snake, Case::Camel, Case::Pascal, Case::Kebab] {
+            assert_eq!(
+                Case::Camel.convert(&case.convert(name)),
+                "parseHttpResponse"
+            );
+        }
+    }
+
+    #[test]
+    fn converts_identifiers_in_code() {
+        let snake = ConvertCase::new(Case::Snake);
+        assert_eq!(
+            snake
+                .apply("let fooBar = __initMe__(x.someField);")
+                .unwrap(),
+            "let foo_bar = __init_me__(x.some_field);"
+        );
+        assert_eq!(
+            snake.apply("0x1F + 1e9 + 2ndValue").unwrap(),
+            "0x1F + 1e9 + 2ndValue"
+        );
+        assert_eq!(
+            snake.apply("len-1 - fooBar-bazQux").unwrap(),
+            "len-1 - foo_bar-baz_qux"
+        );
+        assert_eq!(snake.apply("naïveCafé").unwrap(), "naïve_café");
+    }
+
+    #[test]
+    fn hyphens_join_words_when_asked() {
+        let mut snake = ConvertCase::new(Case::Snake);
+        snake.hyphens = true;
+        assert_eq!(snake.apply("font-size: x-1").unwrap(), "font_size: x-1");
+        let mut camel = ConvertCase::new(Case::Camel);
+        camel.hyphens = true;
+        assert_eq!(camel.apply("a-b - c").unwrap(), "aB - c");
+    }
+
+    #[test]
+    fn per_line_keeps_indentation() {
+        let mut title = ConvertCase::new(Case::Title);
+        title.per_line = true;
+        assert_eq!(
+            title.apply("  some-name_here\r\n\nanother one").unwrap(),
+            "  Some Name Here\r\n\nAnother One"
+        );
+    }
+}
@@ -0,0 +1,295 @@
+use serde::{ser::Error, Serialize, Serializer};
+use serde_json::{Number, Value};
+
+use crate::{xml, Transform};
+
+/// The key toml uses internally to smuggle datetimes through serde.
+const TOML_DATETIME: &str = "$__toml_private_datetime";
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Format {
+    Json,
+    Yaml,
+    Toml,
+    Ron,
+    Xml,
+}
+
+impl Format {
+    pub fn name(self) -> &'static str {
+        match self {
+            Format::Json => "json",
+            Format::Yaml => "yaml",
+            Format::Toml => "toml",
+            Format::Ron => "ron",
+            Format::Xml => "xml",
+        }
+    }
+}
+
+/// Works out which format `input` is in and parses it into the json value model. Xml is
+/// converted with the convention described in the `xml` module.
+///
+/// Formats are tried from strictest to loosest. Almost any text is valid yaml (as a plain
+/// string), so yaml only counts if it produces a mapping or a sequence.
+pub fn detect(input: &str) -> anyhow::Result<(Format, Value)> {
+    if let Ok(value) = serde_json::from_str(input) {
+        return Ok((Format::Json, value));
+    }
+    if input.trim_start().starts_with('<') {
+        return Ok((Format::Xml, xml::to_value(input)?));
+    }
+    if let Ok(table) = input.parse::<toml::Table>() {
+        let mut value = serde_json::to_value(table)?;
+        unwrap_toml_datetimes(&mut value);
+        return Ok((Format::Toml, value));
+    }
+    if let Ok(value) = ron::from_str(input) {
+        return Ok((Format::Ron, from_ron(value)?));
+    }
+    match serde_yaml::from_str(input) {
+        Ok(value @ (Value::Object(_) | Value::Array(_))) => Ok((Format::Yaml, value)),
+        _ => anyhow::bail!("couldn't parse the buffer as json, xml, toml, ron or yaml"),
+    }
+}
+
+/// Replaces the `{"$__toml_private_datetime": "..."}` tables toml produces with plain strings.
+fn unwrap_toml_datetimes(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            if let Some(Value::String(datetime)) = map.get(TOML_DATETIME) {
+                *value = Value::String(datetime.clone());
+                return;
+            }
+            map.values_mut().for_each(unwrap_toml_datetimes);
+        }
+        Value::Array(items) => items.iter_mut().for_each(unwrap_toml_datetimes),
+        _ => (),
+    }
+}
+
+/// Ron structs don't deserialize into the json model directly, so go through ron's own value
+/// type instead. Structs come out as maps keyed by field name.
+fn from_ron(value: ron::Value) -> anyhow::Result<Value> {
+    Ok(match value {
+        ron::Value::Bool(b) => Value::Bool(b),
+        ron::Value::Char(c) => Value::String(c.to_string()),
+        ron::Value::String(s) => Value::String(s),
+        ron::Value::Number(ron::Number::Integer(i)) => Value::from(i),
+        ron::Value::Number(ron::Number::Float(f)) => Value::from(f.get()),
+        ron::Value::Option(Some(v)) => from_ron(*v)?,
+        ron::Value::Option(None) | ron::Value::Unit => Value::Null,
+        ron::Value::Seq(items) => Value::Array(
+            items
+                .into_iter()
+                .map(from_ron)
+                .collect::<anyhow::Result<_>>()?,
+        ),
+        ron::Value::Map(map) => {
+            let mut object = serde_json::Map::new();
+            for (key, value) in map {
+                let ron::Value::String(key) = key else {
+                    anyhow::bail!("only ron maps with string keys can be converted");
+                };
+                object.insert(key, from_ron(value)?);
+            }
+            Value::Object(object)
+        }
+    })
+}
+
+/// Finds the first null in `value`, returning its path like `.servers[2].port`.
+fn find_null(value: &Value, path: &str) -> Option<String> {
+    match value {
+        Value::Null => Some(path.to_string()),
+        Value::Object(map) => map
+            .iter()
+            .find_map(|(key, v)| find_null(v, &format!("{}.{}", path, key))),
+        Value::Array(items) => items
+            .iter()
+            .enumerate()
+            .find_map(|(i, v)| find_null(v, &format!("{}[{}]", path, i))),
+        _ => None,
+    }
+}
+
+/// The significant digits of a number's decimal text, without the sign, point, exponent or
+/// leading and trailing zeros.
+fn significant_digits(text: &str) -> String {
+    let mantissa = text.split(['e', 'E']).next().unwrap_or(text);
+    let digits: String = mantissa.chars().filter(char::is_ascii_digit).collect();
+    digits.trim_matches('0').to_string()
+}
+
+/// A json number as an f64, if the f64 still has every digit the json text had.
+fn exact_f64(number: &Number) -> Option<f64> {
+    let text = number.to_string();
+    let float: f64 = text.parse().ok().filter(|f: &f64| f.is_finite())?;
+    // `{:e}` gives the shortest digits that read back as the same f64.
+    (significant_digits(&text) == significant_digits(&format!("{:e}", float))).then_some(float)
+}
+
+/// Serializes a json value with its numbers as the plain integers and floats other formats
+/// understand. Numbers that wouldn't survive that, like integers too big for 64 bits, are an
+/// error rather than being rounded.
+struct Native<'a>(&'a Value);
+
+impl Serialize for Native<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.0 {
+            Value::Number(n) => {
+                if let Some(i) = n.as_i64() {
+                    serializer.serialize_i64(i)
+                } else if let Some(u) = n.as_u64() {
+                    serializer.serialize_u64(u)
+                } else if let Some(f) = exact_f64(n) {
+                    serializer.serialize_f64(f)
+                } else {
+                    Err(S::Error::custom(format!(
+                        "{} can't be converted exactly",
+                        n
+                    )))
+                }
+            }
+            Value::Array(items) => serializer.collect_seq(items.iter().map(Native)),
+            Value::Object(map) => serializer.collect_map(map.iter().map(|(k, v)| (k, Native(v)))),
+            value => value.serialize(serializer),
+        }
+    }
+}
+
+pub fn write(value: &Value, format: Format) -> anyhow::Result<String> {
+    let native = Native(value);
+    Ok(match format {
+        Format::Json => serde_json::to_string_pretty(value)?,
+        Format::Yaml => serde_yaml::to_string(&native)?,
+        Format::Toml => {
+            if !value.is_object() {
+                anyhow::bail!("toml can only represent a table at the top level");
+            }
+            if let Some(path) = find_null(value, "") {
+                anyhow::bail!("toml can't represent null (found at {})", path);
+            }
+            toml::to_string_pretty(&native)?
+        }
+        Format::Ron => ron::ser::to_string_pretty(&native, ron::ser::PrettyConfig::default())?,
+        Format::Xml => xml::from_value(value)?,
+    })
+}
+
+/// Converts json, yaml, toml, ron or xml in the buffer into the given format.
+pub struct ConvertTo(pub Format);
+
+impl Transform for ConvertTo {
+    fn name(&self) -> &str {
+        match self.0 {
+            Format::Json => "convert to json",
+            Format::Yaml => "convert to yaml",
+            Format::Toml => "convert to toml",
+            Format::Ron => "convert to ron",
+            Format::Xml => "convert to xml",
+        }
+    }
+
+    fn description(&self) -> &str {
+        match self.0 {
+            Format::Json => "convert yaml, toml, ron or xml to json",
+            Format::Yaml => "convert json, toml, ron or xml to yaml",
+            Format::Toml => "convert json, yaml, ron or xml to toml",
+            Format::Ron => "convert json, yaml, toml or xml to ron",
+            Format::Xml => "convert json, yaml, toml or ron to xml",
+        }
+    }
+
+    fn default_key(&self) -> Option<&str> {
+        None
+    }
+
+    fn apply(&self, input: &str) -> anyhow::Result<String> {
+        let (from, value) = detect(input)?;
+        write(&value, self.0)
+            .map_err(|e| anyhow::anyhow!("{} to {}: {}", from.name(), self.0.name(), e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const JSON: &str = r#"{"name": "backflip", "tags": ["a", "b"], "port": 8080, "ratio": 0.5}"#;
+
+    #[test]
+    fn detects_each_format() {
+        let yaml = "name: backflip\ntags:\n- a\n";
+        let toml = "name = \"backflip\"\n[server]\nport = 1\n";
+        let ron = "(name: \"backflip\", tags: [\"a\"])";
+        let xml = "<config><name>backflip</name></config>";
+        for (input, format) in [
+            (JSON, Format::Json),
+            (yaml, Format::Yaml),
+            (toml, Format::Toml),
+            (ron, Format::Ron),
+            (xml, Format::Xml),
+        ] {
+            assert_eq!(detect(input).unwrap().0, format, "{}", input);
+        }
+        assert!(detect("just some words").is_err());
+    }
+
+    #[test]
+    fn conversions_round_trip() {
+        let (_, original) = detect(JSON).unwrap();
+        for format in [Format::Json, Format::Yaml, Format::Toml, Format::Ron] {
+            let written = ConvertTo(format).apply(JSON).unwrap();
+            let (detected, value) = detect(&written).unwrap();
+            assert_eq!(detected, format, "{}", written);
+            assert_eq!(value, original, "{}", written);
+        }
+    }
+
+    #[test]
+    fn toml_rejects_what_it_cant_hold() {
+        let e = ConvertTo(Format::Toml)
+            .apply(r#"{"servers": [{"port": null}]}"#)
+            .unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "json to toml: toml can't represent null (found at .servers[0].port)"
+        );
+        assert!(ConvertTo(Format::Toml).apply("[1, 2]").is_err());
+    }
+
+    #[test]
+    fn toml_keeps_key_order() {
+        assert_eq!(
+            ConvertTo(Format::Json).apply("z = 1\na = 2").unwrap(),
+            "{\n  \"z\": 1,\n  \"a\": 2\n}"
+        );
+        let json = r#"{"z": {"b": 1, "a": 2}, "a": 3}"#;
+        let toml = ConvertTo(Format::Toml).apply(json).unwrap();
+        assert_eq!(toml, "a = 3\n\n[z]\nb = 1\na = 2\n");
+        let yaml = ConvertTo(Format::Yaml).apply(json).unwrap();
+        assert_eq!(yaml, "z:\n  b: 1\n  a: 2\na: 3\n");
+    }
+
+    #[test]
+    fn numbers_convert_exactly_or_not_at_all() {
+        let yaml = ConvertTo(Format::Yaml)
+            .apply(r#"{"i": -5, "u": 18446744073709551615, "f": 0.1, "e": 1e2}"#)
+            .unwrap();
+        assert_eq!(yaml, "i: -5\nu: 18446744073709551615\nf: 0.1\ne: 100.0\n");
+        for number in ["12345678901234567890123", "3.141592653589793238"] {
+            let json = format!(r#"{{"n": {}}}"#, number);
+            let e = ConvertTo(Format::Yaml).apply(&json).unwrap_err();
+            assert!(e.to_string().contains(number), "{}", e);
+        }
+    }
+
+    #[test]
+    fn toml_datetimes_become_strings() {
+        let json = ConvertTo(Format::Json)
+            .apply("when = 1979-05-27T07:32:00Z")
+            .unwrap();
+        assert_eq!(json, "{\n  \"when\": \"1979-05-27T07:32:00Z\"\n}");
+    }
+}
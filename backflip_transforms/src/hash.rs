@@ -0,0 +1,237 @@
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use ::base64::{engine::general_purpose::STANDARD, Engine};
+use md5::Md5;
+use sha1::Sha1;
+use sha2::{Digest, Sha256, Sha512};
+
+use crate::{hex, Job, Setting, Transform};
+
+#[derive(Clone, Copy)]
+pub enum Algorithm {
+    Md5,
+    Sha1,
+    Sha256,
+    Sha512,
+    Blake3,
+    Crc32,
+}
+
+enum Hasher {
+    Md5(Md5),
+    Sha1(Sha1),
+    Sha256(Sha256),
+    Sha512(Sha512),
+    Blake3(Box<blake3::Hasher>),
+    Crc32(crc32fast::Hasher),
+}
+
+impl Hasher {
+    fn new(algorithm: Algorithm) -> Self {
+        match algorithm {
+            Algorithm::Md5 => Hasher::Md5(Md5::new()),
+            Algorithm::Sha1 => Hasher::Sha1(Sha1::new()),
+            Algorithm::Sha256 => Hasher::Sha256(Sha256::new()),
+            Algorithm::Sha512 => Hasher::Sha512(Sha512::new()),
+            Algorithm::Blake3 => Hasher::Blake3(Box::default()),
+            Algorithm::Crc32 => Hasher::Crc32(crc32fast::Hasher::new()),
+        }
+    }
+
+    fn update(&mut self, bytes: &[u8]) {
+        match self {
+            Hasher::Md5(h) => h.update(bytes),
+            Hasher::Sha1(h) => h.update(bytes),
+            Hasher::Sha256(h) => h.update(bytes),
+            Hasher::Sha512(h) => h.update(bytes),
+            Hasher::Blake3(h) => {
+                h.update(bytes);
+            }
+            Hasher::Crc32(h) => h.update(bytes),
+        }
+    }
+
+    fn finalize(self) -> Vec<u8> {
+        match self {
+            Hasher::Md5(h) => h.finalize().to_vec(),
+            Hasher::Sha1(h) => h.finalize().to_vec(),
+            Hasher::Sha256(h) => h.finalize().to_vec(),
+            Hasher::Sha512(h) => h.finalize().to_vec(),
+            Hasher::Blake3(h) => h.finalize().as_bytes().to_vec(),
+            // Big-endian, so the hex matches what `crc32` and friends print.
+            Hasher::Crc32(h) => h.finalize().to_be_bytes().to_vec(),
+        }
+    }
+}
+
+const ENCODINGS: &[&str] = &["hex", "base64"];
+
+/// Digests the buffer's UTF-8 bytes. When the clipboard held files, each file is digested
+/// instead, giving a listing in the same format as `sha256sum`.
+#[derive(Clone)]
+pub struct Hash {
+    pub algorithm: Algorithm,
+    /// An index into `ENCODINGS`.
+    pub encoding: usize,
+    /// Keep the text and add the digest on a line after it, rather than replacing it.
+    pub annotate: bool,
+}
+
+impl Hash {
+    pub fn new(algorithm: Algorithm) -> Self {
+        Hash {
+            algorithm,
+            encoding: 0,
+            annotate: false,
+        }
+    }
+
+    fn encode(&self, digest: &[u8]) -> String {
+        match self.encoding {
+            0 => hex::encode(digest),
+            _ => STANDARD.encode(digest),
+        }
+    }
+
+    fn hash_file(&self, path: &Path) -> std::io::Result<String> {
+        let mut file = std::fs::File::open(path)?;
+        let mut hasher = Hasher::new(self.algorithm);
+        let mut buffer = vec![0; 64 * 1024];
+        loop {
+            match file.read(&mut buffer)? {
+                0 => break,
+                n => hasher.update(&buffer[..n]),
+            }
+        }
+        Ok(self.encode(&hasher.finalize()))
+    }
+}
+
+impl Transform for Hash {
+    fn name(&self) -> &str {
+        match self.algorithm {
+            Algorithm::Md5 => "md5",
+            Algorithm::Sha1 => "sha-1",
+            Algorithm::Sha256 => "sha-256",
+            Algorithm::Sha512 => "sha-512",
+            Algorithm::Blake3 => "blake3",
+            Algorithm::Crc32 => "crc32",
+        }
+    }
+
+    fn description(&self) -> &str {
+        match self.algorithm {
+            Algorithm::Md5 => "hash with md5",
+            Algorithm::Sha1 => "hash with sha-1",
+            Algorithm::Sha256 => "hash with sha-256",
+            Algorithm::Sha512 => "hash with sha-512",
+            Algorithm::Blake3 => "hash with blake3",
+            Algorithm::Crc32 => "checksum with crc32",
+        }
+    }
+
+    fn default_key(&self) -> Option<&str> {
+        None
+    }
+
+    fn apply(&self, input: &str) -> anyhow::Result<String> {
+        let mut hasher = Hasher::new(self.algorithm);
+        hasher.update(input.as_bytes());
+        let digest = self.encode(&hasher.finalize());
+        if !self.annotate {
+            return Ok(digest);
+        }
+        let separator = if input.ends_with('\n') { "" } else { "\n" };
+        Ok(format!("{}{}{}: {}", input, separator, self.name(), digest))
+    }
+
+    fn apply_files(&self, files: &[PathBuf]) -> Option<Job> {
+        let hash = self.clone();
+        let files = files.to_vec();
+        Some(Box::new(move || {
+            let lines: Vec<String> = files
+                .iter()
+                .map(|path| match hash.hash_file(path) {
+                    Ok(digest) => format!("{}  {}", digest, path.display()),
+                    // Keep going, like sha256sum does, so one directory or locked file doesn't
+                    // lose the rest of the listing.
+                    Err(e) => format!("{}: {}", path.display(), e),
+                })
+                .collect();
+            Ok(lines.join("\n"))
+        }))
+    }
+
+    fn settings(&mut self) -> Vec<Setting<'_>> {
+        vec![
+            Setting::Choice {
+                label: "encoding",
+                value: &mut self.encoding,
+                options: ENCODINGS,
+            },
+            Setting::Toggle {
+                label: "append to text",
+                value: &mut self.annotate,
+            },
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn known_digests() {
+        let digest = |algorithm| Hash::new(algorithm).apply("abc").unwrap();
+        assert_eq!(digest(Algorithm::Md5), "900150983cd24fb0d6963f7d28e17f72");
+        assert_eq!(
+            digest(Algorithm::Sha1),
+            "a9993e364706816aba3e25717850c26c9cd0d89d"
+        );
+        assert_eq!(
+            digest(Algorithm::Sha256),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_eq!(digest(Algorithm::Sha512).len(), 128);
+        assert_eq!(digest(Algorithm::Blake3).len(), 64);
+        assert_eq!(digest(Algorithm::Crc32), "352441c2");
+    }
+
+    #[test]
+    fn encodes_and_annotates() {
+        let mut hash = Hash::new(Algorithm::Sha256);
+        hash.encoding = 1;
+        assert_eq!(
+            hash.apply("abc").unwrap(),
+            "ungWv48Bz+pBQUDeXa4iI7ADYaOWF3qctBD/YfIAFa0="
+        );
+        let mut hash = Hash::new(Algorithm::Crc32);
+        hash.annotate = true;
+        assert_eq!(hash.apply("abc").unwrap(), "abc\ncrc32: 352441c2");
+        assert!(hash.apply("abc\n").unwrap().starts_with("abc\ncrc32: "));
+    }
+
+    #[test]
+    fn hashes_files_in_a_job() {
+        let dir = std::env::temp_dir().join(format!("backflip-hash-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("abc.txt");
+        std::fs::write(&file, "abc").unwrap();
+        let missing = dir.join("missing.txt");
+
+        let job = Hash::new(Algorithm::Md5)
+            .apply_files(&[file.clone(), missing.clone()])
+            .unwrap();
+        let listing = std::thread::spawn(job).join().unwrap().unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let lines: Vec<&str> = listing.lines().collect();
+        assert_eq!(
+            lines[0],
+            format!("900150983cd24fb0d6963f7d28e17f72  {}", file.display())
+        );
+        assert!(lines[1].starts_with(&format!("{}: ", missing.display())));
+    }
+}
@@ -0,0 +1,244 @@
+use std::sync::OnceLock;
+
+use ::regex::Regex;
+
+use crate::{Setting, Transform};
+
+/// Formats bytes the way `xxd` does: an offset, sixteen bytes of hex in pairs, and an ASCII
+/// column with `.` for anything unprintable.
+pub fn dump(bytes: &[u8]) -> String {
+    dump_with(bytes, 16, 2)
+}
+
+/// `dump` with `columns` bytes per line, grouped `group` bytes at a time like `xxd -c -g`.
+pub fn dump_with(bytes: &[u8], columns: usize, group: usize) -> String {
+    let columns = columns.max(1);
+    let group = group.max(1);
+    let width = columns * 2 + (columns - 1) / group;
+    let mut out = String::new();
+    for (line, chunk) in bytes.chunks(columns).enumerate() {
+        let mut hex = String::new();
+        for (i, byte) in chunk.iter().enumerate() {
+            if i > 0 && i % group == 0 {
+                hex.push(' ');
+            }
+            hex.push_str(&format!("{:02x}", byte));
+        }
+        let ascii: String = chunk
+            .iter()
+            .map(|&b| {
+                if b.is_ascii_graphic() || b == b' ' {
+                    b as char
+                } else {
+                    '.'
+                }
+            })
+            .collect();
+        out.push_str(&format!(
+            "{:08x}: {:<width$}  {}\n",
+            line * columns,
+            hex,
+            ascii,
+            width = width
+        ));
+    }
+    out
+}
+
+/// Bytes as plain lowercase hex, with nothing between them.
+pub fn encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Decoded bytes as text if they're UTF-8, or as a hex dump if they aren't.
+pub fn text_or_dump(bytes: Vec<u8>) -> String {
+    match String::from_utf8(bytes) {
+        Ok(s) => s,
+        Err(e) => dump(e.as_bytes()),
+    }
+}
+
+fn dump_line_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| Regex::new(r"^\s*[0-9a-fA-F]{4,}: ").unwrap())
+}
+
+/// Reads bytes back out of `input`, which is either a dump in the format `dump` writes, or hex
+/// on its own. Plain hex can be split up by spaces, commas, colons or dashes, and each piece can
+/// have a `0x` prefix, so `de ad be ef`, `DE:AD:BE:EF` and `0xde, 0xad, 0xbe, 0xef` all work.
+pub fn parse(input: &str) -> anyhow::Result<Vec<u8>> {
+    let lines: Vec<&str> = input.lines().filter(|l| !l.trim().is_empty()).collect();
+    let is_dump = !lines.is_empty() && lines.iter().all(|l| dump_line_regex().is_match(l));
+    let mut digits = String::new();
+    if is_dump {
+        for line in lines {
+            let (_, rest) = line.split_once(": ").unwrap_or_default();
+            // The hex ends where the two spaces before the ASCII column start.
+            let hex = rest.split("  ").next().unwrap_or_default();
+            digits.extend(hex.chars().filter(|c| !c.is_whitespace()));
+        }
+    } else {
+        for piece in input.split(|c: char| c.is_whitespace() || ",:-".contains(c)) {
+            let Some(hex) = piece
+                .strip_prefix("0x")
+                .or_else(|| piece.strip_prefix("0X"))
+            else {
+                digits.push_str(piece);
+                continue;
+            };
+            // `0xa` is a whole byte, not half of one.
+            if hex.len() % 2 == 1 {
+                digits.push('0');
+            }
+            digits.push_str(hex);
+        }
+    }
+
+    if let Some((i, c)) = digits.char_indices().find(|(_, c)| !c.is_ascii_hexdigit()) {
+        anyhow::bail!("{:?} isn't a hex digit (digit {})", c, i + 1);
+    }
+    if digits.len() % 2 == 1 {
+        anyhow::bail!("there's an odd number of hex digits ({})", digits.len());
+    }
+    Ok((0..digits.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&digits[i..i + 2], 16).unwrap())
+        .collect())
+}
+
+/// Dumps the buffer's UTF-8 bytes like `xxd`.
+pub struct HexDump {
+    pub columns: usize,
+    pub group: usize,
+}
+
+impl Default for HexDump {
+    fn default() -> Self {
+        HexDump {
+            columns: 16,
+            group: 2,
+        }
+    }
+}
+
+impl Transform for HexDump {
+    fn name(&self) -> &str {
+        "hex dump"
+    }
+
+    fn description(&self) -> &str {
+        "show the bytes as an xxd-style hex dump"
+    }
+
+    fn default_key(&self) -> Option<&str> {
+        Some("X")
+    }
+
+    fn apply(&self, input: &str) -> anyhow::Result<String> {
+        Ok(dump_with(input.as_bytes(), self.columns, self.group))
+    }
+
+    fn settings(&mut self) -> Vec<Setting<'_>> {
+        vec![
+            Setting::Number {
+                label: "bytes per line",
+                value: &mut self.columns,
+                range: 1..=64,
+            },
+            Setting::Number {
+                label: "bytes per group",
+                value: &mut self.group,
+                range: 1..=16,
+            },
+        ]
+    }
+}
+
+/// The reverse of `HexDump`, which also takes plain hex.
+pub struct HexDecode;
+
+impl Transform for HexDecode {
+    fn name(&self) -> &str {
+        "hex decode"
+    }
+
+    fn description(&self) -> &str {
+        "decode a hex dump or a hex string"
+    }
+
+    fn default_key(&self) -> Option<&str> {
+        None
+    }
+
+    fn apply(&self, input: &str) -> anyhow::Result<String> {
+        Ok(text_or_dump(parse(input)?))
+    }
+
+    fn apply_bytes(&self, input: &str) -> Option<anyhow::Result<Vec<u8>>> {
+        Some(parse(input))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dumps_like_xxd() {
+        assert_eq!(
+            dump(b"hello, world!\n\x00\xff\x7f"),
+            "00000000: 6865 6c6c 6f2c 2077 6f72 6c64 210a 00ff  hello, world!...\n\
+             00000010: 7f                                       .\n"
+        );
+        assert_eq!(dump(b""), "");
+        assert_eq!(
+            dump_with(b"abc", 2, 1),
+            "00000000: 61 62  ab\n00000002: 63     c\n"
+        );
+    }
+
+    #[test]
+    fn dumps_round_trip() {
+        let bytes: Vec<u8> = (0..=255).chain(b"  :  ".iter().copied()).collect();
+        for (columns, group) in [(16, 2), (8, 1), (1, 1), (7, 3), (64, 16)] {
+            let dumped = dump_with(&bytes, columns, group);
+            assert_eq!(parse(&dumped).unwrap(), bytes, "{} {}", columns, group);
+        }
+    }
+
+    #[test]
+    fn parses_plain_hex() {
+        for input in [
+            "deadbeef",
+            "de ad be ef",
+            "DE:AD:BE:EF",
+            "de-ad-be-ef",
+            "0xde, 0xad, 0xbe, 0xef",
+            "0xDEADBEEF\n",
+        ] {
+            assert_eq!(parse(input).unwrap(), [0xde, 0xad, 0xbe, 0xef], "{}", input);
+        }
+        assert_eq!(parse("0xa 0xb").unwrap(), [0x0a, 0x0b]);
+        assert!(parse("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn rejects_bad_hex() {
+        let e = parse("abc").unwrap_err().to_string();
+        assert_eq!(e, "there's an odd number of hex digits (3)");
+        let e = parse("ab zz").unwrap_err().to_string();
+        assert_eq!(e, "'z' isn't a hex digit (digit 3)");
+        assert!(parse("日本").is_err());
+    }
+
+    #[test]
+    fn decodes_to_text_or_bytes() {
+        assert_eq!(HexDecode.apply("68 69").unwrap(), "hi");
+        assert_eq!(HexDecode.apply_bytes("ff00").unwrap().unwrap(), [0xff, 0]);
+        assert!(HexDecode
+            .apply("ff00")
+            .unwrap()
+            .starts_with("00000000: ff00"));
+        assert_eq!(encode(&[0, 0xab]), "00ab");
+    }
+}
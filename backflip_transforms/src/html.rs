@@ -0,0 +1,133 @@
+use std::sync::OnceLock;
+
+use ::regex::Regex;
+
+use crate::{Setting, Transform};
+
+/// Escapes the characters that mean something in HTML and XML, so the text can go in an
+/// element or a quoted attribute.
+pub struct EncodeEntities {
+    /// Also write anything outside ASCII as a numeric reference, for pages that aren't UTF-8.
+    pub non_ascii: bool,
+}
+
+impl Transform for EncodeEntities {
+    fn name(&self) -> &str {
+        "html entity encode"
+    }
+
+    fn description(&self) -> &str {
+        "escape < > & and quotes as html entities"
+    }
+
+    fn default_key(&self) -> Option<&str> {
+        None
+    }
+
+    fn apply(&self, input: &str) -> anyhow::Result<String> {
+        let mut out = String::with_capacity(input.len());
+        for c in input.chars() {
+            match c {
+                '&' => out.push_str("&amp;"),
+                '<' => out.push_str("&lt;"),
+                '>' => out.push_str("&gt;"),
+                '"' => out.push_str("&quot;"),
+                '\'' => out.push_str("&#39;"),
+                c if self.non_ascii && !c.is_ascii() => {
+                    out.push_str(&format!("&#x{:X};", c as u32))
+                }
+                c => out.push(c),
+            }
+        }
+        Ok(out)
+    }
+
+    fn settings(&mut self) -> Vec<Setting<'_>> {
+        vec![Setting::Toggle {
+            label: "encode non-ascii",
+            value: &mut self.non_ascii,
+        }]
+    }
+}
+
+fn entity_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| {
+        Regex::new(r"&(?:#([0-9]{1,7})|#[xX]([0-9a-fA-F]{1,6})|([A-Za-z][A-Za-z0-9]*));").unwrap()
+    })
+}
+
+/// Replaces named and numeric character references with the characters they stand for.
+/// Anything that isn't a reference HTML knows, like a stray `&`, is left alone.
+pub struct DecodeEntities;
+
+impl Transform for DecodeEntities {
+    fn name(&self) -> &str {
+        "html entity decode"
+    }
+
+    fn description(&self) -> &str {
+        "decode named and numeric html entities"
+    }
+
+    fn default_key(&self) -> Option<&str> {
+        None
+    }
+
+    fn apply(&self, input: &str) -> anyhow::Result<String> {
+        Ok(entity_regex()
+            .replace_all(input, |captures: &::regex::Captures| {
+                let code_point = match (captures.get(1), captures.get(2)) {
+                    (Some(decimal), _) => decimal.as_str().parse().ok(),
+                    (_, Some(hex)) => u32::from_str_radix(hex.as_str(), 16).ok(),
+                    // quick-xml knows the whole HTML5 entity table.
+                    _ => {
+                        return quick_xml::escape::unescape(&captures[0])
+                            .map_or_else(|_| captures[0].to_string(), |s| s.into_owned())
+                    }
+                };
+                // Browsers turn references to nothing, or to surrogates, into U+FFFD.
+                code_point
+                    .filter(|&c| c != 0)
+                    .and_then(char::from_u32)
+                    .unwrap_or(char::REPLACEMENT_CHARACTER)
+                    .to_string()
+            })
+            .into_owned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_round_trips() {
+        let text = r#"<a href="x?a=1&b='2'">Æ 😀</a>"#;
+        let encoded = EncodeEntities { non_ascii: false }.apply(text).unwrap();
+        assert_eq!(
+            encoded,
+            "&lt;a href=&quot;x?a=1&amp;b=&#39;2&#39;&quot;&gt;Æ 😀&lt;/a&gt;"
+        );
+        assert_eq!(DecodeEntities.apply(&encoded).unwrap(), text);
+        let ascii = EncodeEntities { non_ascii: true }.apply(text).unwrap();
+        assert!(ascii.ends_with("&#xC6; &#x1F600;&lt;/a&gt;"));
+        assert_eq!(DecodeEntities.apply(&ascii).unwrap(), text);
+    }
+
+    #[test]
+    fn decodes_like_a_browser() {
+        assert_eq!(
+            DecodeEntities.apply("&eacute;&nbsp;&#65;&#x42;").unwrap(),
+            "é\u{a0}AB"
+        );
+        assert_eq!(
+            DecodeEntities.apply("&#0;&#xD800;").unwrap(),
+            "\u{fffd}\u{fffd}"
+        );
+        assert_eq!(
+            DecodeEntities.apply("AT&T &bogus; & &#;").unwrap(),
+            "AT&T &bogus; & &#;"
+        );
+    }
+}
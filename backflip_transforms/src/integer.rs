@@ -0,0 +1,295 @@
+use std::ops::Range;
+use std::sync::OnceLock;
+
+use ::regex::Regex;
+
+use crate::{Setting, Transform};
+
+const BASES: &[&str] = &["decimal", "hex", "octal", "binary"];
+
+/// The entries of `BASES`, for picking one without its index.
+#[derive(Clone, Copy)]
+pub enum Base {
+    Decimal,
+    Hex,
+    Octal,
+    Binary,
+}
+
+/// An integer literal found in some text.
+pub struct Found {
+    /// Byte offsets of the literal in the text.
+    pub range: Range<usize>,
+    pub value: i128,
+}
+
+fn integer_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| {
+        Regex::new(r"-?\b(?:0[xX][0-9a-fA-F_]+|0[oO][0-7_]+|0[bB][01_]+|[0-9][0-9_]*)\b").unwrap()
+    })
+}
+
+/// Parses an integer literal like `-42`, `0xff`, `0o17`, `0b1010` or `1_000_000`.
+pub fn parse(literal: &str) -> Option<i128> {
+    let (negative, literal) = match literal.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, literal),
+    };
+    let (radix, digits) = match literal.get(..2) {
+        Some("0x" | "0X") => (16, &literal[2..]),
+        Some("0o" | "0O") => (8, &literal[2..]),
+        Some("0b" | "0B") => (2, &literal[2..]),
+        _ => (10, literal),
+    };
+    let digits = digits.replace('_', "");
+    // from_str_radix would take a second sign.
+    if digits.is_empty() || digits.starts_with(['+', '-']) {
+        return None;
+    }
+    let value = u128::from_str_radix(&digits, radix).ok()?;
+    if negative {
+        0i128.checked_sub_unsigned(value)
+    } else {
+        i128::try_from(value).ok()
+    }
+}
+
+/// Whether `c` joins numbers into something bigger, like a date, time, path or version.
+fn joiner(c: Option<char>) -> bool {
+    matches!(c, Some('.' | '-' | '/' | ':'))
+}
+
+/// Finds integer literals. Prefixed literals like `0xff` are found anywhere they stand as a
+/// word, but a decimal number has to stand alone, so the parts of `1.5`, `2024-01-15`, `12:30`
+/// or `item-3` are left out. A `-` after a word, as in `0x10-0x2`, is a hyphen rather than a
+/// minus sign.
+pub fn find(input: &str) -> Vec<Found> {
+    integer_regex()
+        .find_iter(input)
+        .filter_map(|m| {
+            let mut start = m.start();
+            let word = |c: Option<char>| c.is_some_and(char::is_alphanumeric);
+            if m.as_str().starts_with('-') && word(input[..start].chars().next_back()) {
+                start += 1;
+            }
+            let mut before = input[..start].chars().rev();
+            let (before, before_that) = (before.next(), before.next());
+            let mut after = input[m.end()..].chars();
+            let (after, after_that) = (after.next(), after.next());
+            let literal = &input[start..m.end()];
+            let prefixed = literal
+                .trim_start_matches('-')
+                .as_bytes()
+                .get(1)
+                .is_some_and(u8::is_ascii_alphabetic);
+            let fraction = after == Some('.') && after_that.is_some_and(|c| c.is_ascii_digit());
+            let joined =
+                (joiner(before) && word(before_that)) || (joiner(after) && word(after_that));
+            if before == Some('.') || fraction || (!prefixed && joined) {
+                return None;
+            }
+            Some(Found {
+                range: start..m.end(),
+                value: parse(literal)?,
+            })
+        })
+        .collect()
+}
+
+/// Writes `digits` with a `_` between every `size` of them, counting from the right.
+fn group(digits: &str, size: usize) -> String {
+    let digits: Vec<char> = digits.chars().collect();
+    let first = match digits.len() % size {
+        0 => size.min(digits.len()),
+        n => n,
+    };
+    let mut out: String = digits[..first].iter().collect();
+    for chunk in digits[first..].chunks(size) {
+        out.push('_');
+        out.extend(chunk);
+    }
+    out
+}
+
+/// Writes `value` as a literal in one of `BASES`.
+pub fn to_base(value: i128, base: usize, separators: bool) -> String {
+    let magnitude = value.unsigned_abs();
+    let (prefix, digits, size) = match BASES[base] {
+        "hex" => ("0x", format!("{:x}", magnitude), 4),
+        "octal" => ("0o", format!("{:o}", magnitude), 3),
+        "binary" => ("0b", format!("{:b}", magnitude), 4),
+        _ => ("", magnitude.to_string(), 3),
+    };
+    let digits = if separators {
+        group(&digits, size)
+    } else {
+        digits
+    };
+    let sign = if value < 0 { "-" } else { "" };
+    format!("{}{}{}", sign, prefix, digits)
+}
+
+/// How an integer is stored in a fixed number of bits.
+pub struct Representation {
+    pub bits: u32,
+    /// Whether the value fits without being truncated, as either a signed or unsigned integer.
+    pub fits: bool,
+    pub unsigned: u64,
+    pub signed: i64,
+    pub little_endian: Vec<u8>,
+    pub big_endian: Vec<u8>,
+}
+
+/// The value as an 8, 16, 32 and 64-bit integer. Values that don't fit are truncated to the low
+/// bits, the way a cast would.
+pub fn representations(value: i128) -> Vec<Representation> {
+    [8u32, 16, 32, 64]
+        .into_iter()
+        .map(|bits| {
+            let shift = 64 - bits;
+            let unsigned = (value as u64) << shift >> shift;
+            let signed = ((value as u64) << shift) as i64 >> shift;
+            let bytes = (bits / 8) as usize;
+            Representation {
+                bits,
+                fits: value == unsigned as i128 || value == signed as i128,
+                unsigned,
+                signed,
+                little_endian: unsigned.to_le_bytes()[..bytes].to_vec(),
+                big_endian: unsigned.to_be_bytes()[8 - bytes..].to_vec(),
+            }
+        })
+        .collect()
+}
+
+/// Rewrites every integer in the buffer in another base.
+#[derive(Default)]
+pub struct ConvertBase {
+    /// An index into `BASES`.
+    pub base: usize,
+    /// Group digits with `_`, in threes for decimal and octal and fours for hex and binary.
+    pub separators: bool,
+}
+
+impl ConvertBase {
+    pub fn new(base: Base) -> Self {
+        ConvertBase {
+            base: base as usize,
+            separators: false,
+        }
+    }
+}
+
+impl Transform for ConvertBase {
+    fn name(&self) -> &str {
+        "convert number base"
+    }
+
+    fn description(&self) -> &str {
+        "convert integers to decimal, hex, octal or binary"
+    }
+
+    fn default_key(&self) -> Option<&str> {
+        None
+    }
+
+    fn apply(&self, input: &str) -> anyhow::Result<String> {
+        let mut out = String::with_capacity(input.len());
+        let mut last = 0;
+        for found in find(input) {
+            out.push_str(&input[last..found.range.start]);
+            out.push_str(&to_base(found.value, self.base, self.separators));
+            last = found.range.end;
+        }
+        out.push_str(&input[last..]);
+        Ok(out)
+    }
+
+    fn settings(&mut self) -> Vec<Setting<'_>> {
+        vec![
+            Setting::Choice {
+                label: "base",
+                value: &mut self.base,
+                options: BASES,
+            },
+            Setting::Toggle {
+                label: "group digits with _",
+                value: &mut self.separators,
+            },
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn convert(input: &str, base: Base) -> String {
+        ConvertBase::new(base).apply(input).unwrap()
+    }
+
+    #[test]
+    fn parses_literals() {
+        assert_eq!(parse("-42"), Some(-42));
+        assert_eq!(parse("0xFF"), Some(255));
+        assert_eq!(parse("0o17"), Some(15));
+        assert_eq!(parse("0b1010"), Some(10));
+        assert_eq!(parse("1_000_000"), Some(1_000_000));
+        assert_eq!(parse("-0x8000000000000000"), Some(-(1 << 63)));
+        for bad in ["", "-", "0x", "0x_", "--1", "0x+1", "12a"] {
+            assert_eq!(parse(bad), None, "{}", bad);
+        }
+    }
+
+    #[test]
+    fn converts_round_trip() {
+        let text = "x = 255, y = -0x10, z = 0b11";
+        let hex = convert(text, Base::Hex);
+        assert_eq!(hex, "x = 0xff, y = -0x10, z = 0x3");
+        assert_eq!(convert(&hex, Base::Decimal), "x = 255, y = -16, z = 3");
+        assert_eq!(convert(text, Base::Octal), "x = 0o377, y = -0o20, z = 0o3");
+        assert_eq!(
+            convert(text, Base::Binary),
+            "x = 0b11111111, y = -0b10000, z = 0b11"
+        );
+    }
+
+    #[test]
+    fn leaves_dates_versions_and_times_alone() {
+        for text in [
+            "2024-01-15",
+            "released 1.2.3 on 2024/01/15",
+            "at 12:30:05",
+            "3.14",
+            "item-3",
+            "192.168.0.1",
+        ] {
+            assert_eq!(convert(text, Base::Hex), text);
+        }
+        assert_eq!(convert("5 - 3 = 2.", Base::Hex), "0x5 - 0x3 = 0x2.");
+        assert_eq!(convert("mask 0x10-0x2", Base::Decimal), "mask 16-2");
+    }
+
+    #[test]
+    fn groups_digits() {
+        assert_eq!(to_base(1234567, Base::Decimal as usize, true), "1_234_567");
+        assert_eq!(to_base(-0xabcde, Base::Hex as usize, true), "-0xa_bcde");
+        assert_eq!(to_base(0o777, Base::Octal as usize, true), "0o777");
+        assert_eq!(to_base(5, Base::Binary as usize, true), "0b101");
+    }
+
+    #[test]
+    fn truncates_like_a_cast() {
+        let reps = representations(-1);
+        assert!(reps.iter().all(|r| r.fits));
+        assert_eq!(reps[0].unsigned, 0xff);
+        assert_eq!(reps[1].big_endian, [0xff, 0xff]);
+        let reps = representations(0x1234);
+        assert!(!reps[0].fits);
+        assert_eq!(reps[0].unsigned, 0x34);
+        assert_eq!(reps[1].little_endian, [0x34, 0x12]);
+        assert_eq!(reps[3].signed, 0x1234);
+        assert!(!representations(1 << 64)[3].fits);
+    }
+}
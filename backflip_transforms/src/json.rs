@@ -0,0 +1,308 @@
+use serde::Serialize;
+use serde_json::{ser::PrettyFormatter, Value};
+
+use crate::{Setting, Transform};
+
+/// Wraps the buffer in a JSON string literal.
+pub struct SerializeJson;
+
+impl Transform for SerializeJson {
+    fn name(&self) -> &str {
+        "serialize json"
+    }
+
+    fn description(&self) -> &str {
+        "wrap the text in a json string literal"
+    }
+
+    fn default_key(&self) -> Option<&str> {
+        Some("S")
+    }
+
+    fn apply(&self, input: &str) -> anyhow::Result<String> {
+        Ok(serde_json::to_string(input)?)
+    }
+}
+
+/// Unwraps a JSON string literal.
+pub struct DeserializeJson;
+
+impl Transform for DeserializeJson {
+    fn name(&self) -> &str {
+        "deserialize json"
+    }
+
+    fn description(&self) -> &str {
+        "unwrap a json string literal"
+    }
+
+    fn default_key(&self) -> Option<&str> {
+        Some("A")
+    }
+
+    fn apply(&self, input: &str) -> anyhow::Result<String> {
+        Ok(serde_json::from_str(input)?)
+    }
+}
+
+/// Parses a JSON document. Errors point at the line and column where parsing failed, with an
+/// excerpt of the input around it.
+pub fn parse(input: &str) -> anyhow::Result<Value> {
+    serde_json::from_str(input).map_err(|e| {
+        let line: Vec<char> = input
+            .lines()
+            .nth(e.line().saturating_sub(1))
+            .unwrap_or_default()
+            .chars()
+            .collect();
+        // Minified documents are one very long line, so only show the neighbourhood.
+        let column = e.column().saturating_sub(1).min(line.len());
+        let start = column.saturating_sub(30);
+        let end = (column + 30).min(line.len());
+        let excerpt: String = line[start..end].iter().collect();
+        anyhow::anyhow!("{}\n{}\n{}^", e, excerpt, " ".repeat(column - start))
+    })
+}
+
+fn to_string_indented(value: &Value, indent: &[u8]) -> anyhow::Result<String> {
+    let mut out = Vec::new();
+    let formatter = PrettyFormatter::with_indent(indent);
+    value.serialize(&mut serde_json::Serializer::with_formatter(
+        &mut out, formatter,
+    ))?;
+    Ok(String::from_utf8(out)?)
+}
+
+fn sort_keys(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            // Map keeps insertion order with preserve_order, so rebuild it in sorted order.
+            let mut entries: Vec<(String, Value)> = std::mem::take(map).into_iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+            map.extend(entries);
+            map.values_mut().for_each(sort_keys);
+        }
+        Value::Array(items) => items.iter_mut().for_each(sort_keys),
+        _ => (),
+    }
+}
+
+/// Removes object members whose value is null. Nulls inside arrays are kept, since removing
+/// them would shift the positions of everything after them.
+fn strip_nulls(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            map.retain(|_, v| !v.is_null());
+            map.values_mut().for_each(strip_nulls);
+        }
+        Value::Array(items) => items.iter_mut().for_each(strip_nulls),
+        _ => (),
+    }
+}
+
+pub struct PrettyJson {
+    pub indent: usize,
+    pub tabs: bool,
+}
+
+impl Default for PrettyJson {
+    fn default() -> Self {
+        PrettyJson {
+            indent: 2,
+            tabs: false,
+        }
+    }
+}
+
+impl Transform for PrettyJson {
+    fn name(&self) -> &str {
+        "pretty print json"
+    }
+
+    fn description(&self) -> &str {
+        "pretty-print a json document"
+    }
+
+    fn default_key(&self) -> Option<&str> {
+        Some("P")
+    }
+
+    fn apply(&self, input: &str) -> anyhow::Result<String> {
+        let indent = if self.tabs {
+            "\t".to_string()
+        } else {
+            " ".repeat(self.indent)
+        };
+        to_string_indented(&parse(input)?, indent.as_bytes())
+    }
+
+    fn settings(&mut self) -> Vec<Setting<'_>> {
+        vec![
+            Setting::Number {
+                label: "indent",
+                value: &mut self.indent,
+                range: 1..=8,
+            },
+            Setting::Toggle {
+                label: "indent with tabs",
+                value: &mut self.tabs,
+            },
+        ]
+    }
+}
+
+pub struct MinifyJson;
+
+impl Transform for MinifyJson {
+    fn name(&self) -> &str {
+        "minify json"
+    }
+
+    fn description(&self) -> &str {
+        "remove all whitespace from a json document"
+    }
+
+    fn default_key(&self) -> Option<&str> {
+        Some("M")
+    }
+
+    fn apply(&self, input: &str) -> anyhow::Result<String> {
+        Ok(serde_json::to_string(&parse(input)?)?)
+    }
+}
+
+/// Sorted keys and no whitespace, so that equal documents produce identical text.
+pub struct CanonicalJson;
+
+impl Transform for CanonicalJson {
+    fn name(&self) -> &str {
+        "canonicalize json"
+    }
+
+    fn description(&self) -> &str {
+        "sort object keys recursively and minify"
+    }
+
+    fn default_key(&self) -> Option<&str> {
+        None
+    }
+
+    fn apply(&self, input: &str) -> anyhow::Result<String> {
+        let mut value = parse(input)?;
+        sort_keys(&mut value);
+        Ok(serde_json::to_string(&value)?)
+    }
+}
+
+pub struct StripJsonNulls;
+
+impl Transform for StripJsonNulls {
+    fn name(&self) -> &str {
+        "strip json nulls"
+    }
+
+    fn description(&self) -> &str {
+        "remove object members that are null"
+    }
+
+    fn default_key(&self) -> Option<&str> {
+        None
+    }
+
+    fn apply(&self, input: &str) -> anyhow::Result<String> {
+        let mut value = parse(input)?;
+        strip_nulls(&mut value);
+        match indentation(input) {
+            Some(indent) => to_string_indented(&value, indent.as_bytes()),
+            None => Ok(serde_json::to_string(&value)?),
+        }
+    }
+}
+
+/// The indentation a pretty-printed document uses, or `None` if it's on one line.
+fn indentation(input: &str) -> Option<&str> {
+    if !input.trim().contains('\n') {
+        return None;
+    }
+    let indent = input
+        .lines()
+        .map(|line| &line[..line.len() - line.trim_start().len()])
+        .find(|indent| !indent.is_empty());
+    // A multi-line document without any indentation, like `[\n1\n]`.
+    Some(indent.unwrap_or("  "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serialize_round_trips() {
+        let text = "say \"hi\"\n\tüñí";
+        let literal = SerializeJson.apply(text).unwrap();
+        assert_eq!(literal, r#""say \"hi\"\n\tüñí""#);
+        assert_eq!(DeserializeJson.apply(&literal).unwrap(), text);
+        assert!(DeserializeJson.apply("{}").is_err());
+    }
+
+    #[test]
+    fn pretty_and_minify_round_trip() {
+        let minified = r#"{"b":[1,{"c":null}],"a":"x"}"#;
+        let pretty = PrettyJson::default().apply(minified).unwrap();
+        assert_eq!(
+            pretty,
+            "{\n  \"b\": [\n    1,\n    {\n      \"c\": null\n    }\n  ],\n  \"a\": \"x\"\n}"
+        );
+        assert_eq!(MinifyJson.apply(&pretty).unwrap(), minified);
+        let tabs = PrettyJson {
+            indent: 2,
+            tabs: true,
+        };
+        assert!(tabs.apply("[1]").unwrap().contains("\n\t1"));
+    }
+
+    #[test]
+    fn numbers_keep_their_text() {
+        let input = r#"{"big": 12345678901234567890123, "f": 1e2, "pi": 3.141592653589793238}"#;
+        let minified = r#"{"big":12345678901234567890123,"f":1e2,"pi":3.141592653589793238}"#;
+        assert_eq!(MinifyJson.apply(input).unwrap(), minified);
+        assert_eq!(
+            CanonicalJson.apply(input).unwrap(),
+            r#"{"big":12345678901234567890123,"f":1e2,"pi":3.141592653589793238}"#
+        );
+        assert_eq!(StripJsonNulls.apply(input).unwrap(), minified);
+        let pretty = PrettyJson::default().apply(input).unwrap();
+        assert!(pretty.contains("\"big\": 12345678901234567890123,\n  \"f\": 1e2,"));
+    }
+
+    #[test]
+    fn parse_errors_point_at_the_column() {
+        let e = parse(r#"{"a": tru}"#).unwrap_err().to_string();
+        assert!(e.ends_with("{\"a\": tru}\n         ^"), "{}", e);
+    }
+
+    #[test]
+    fn canonical_sorts_nested_keys() {
+        let input = r#"{"b": {"z": 1, "y": [{"d": 1, "c": 2}]}, "a": null}"#;
+        assert_eq!(
+            CanonicalJson.apply(input).unwrap(),
+            r#"{"a":null,"b":{"y":[{"c":2,"d":1}],"z":1}}"#
+        );
+    }
+
+    #[test]
+    fn strip_nulls_keeps_layout() {
+        assert_eq!(
+            StripJsonNulls
+                .apply(r#"{"a":null,"b":[null,{"c":null}]}"#)
+                .unwrap(),
+            r#"{"b":[null,{}]}"#
+        );
+        assert_eq!(
+            StripJsonNulls
+                .apply("{\n    \"a\": 1,\n    \"b\": null\n}")
+                .unwrap(),
+            "{\n    \"a\": 1\n}"
+        );
+    }
+}
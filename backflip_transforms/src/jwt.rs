@@ -0,0 +1,248 @@
+use ::base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{DateTime, Utc};
+use jsonwebtoken::{Algorithm, DecodingKey};
+use serde_json::{json, Value};
+
+use crate::Transform;
+
+/// The registered claims that hold dates, as seconds since the epoch.
+const TIME_CLAIMS: &[(&str, &str)] =
+    &[("exp", "expires"), ("nbf", "not before"), ("iat", "issued")];
+
+/// A JSON Web Token split into its parts. Decoding doesn't check the signature.
+pub struct Token {
+    pub header: Value,
+    pub payload: Value,
+    /// The encoded header and payload, which is what the signature covers.
+    message: String,
+    signature: String,
+}
+
+pub struct Time {
+    pub claim: &'static str,
+    pub label: &'static str,
+    pub date: DateTime<Utc>,
+}
+
+pub enum Validity {
+    Valid,
+    Expired,
+    NotYetValid,
+}
+
+fn decode_part(part: &str, what: &str) -> anyhow::Result<Value> {
+    let bytes = URL_SAFE_NO_PAD
+        .decode(part.trim_end_matches('='))
+        .map_err(|e| anyhow::anyhow!("the {} isn't base64url: {}", what, e))?;
+    serde_json::from_slice(&bytes).map_err(|e| anyhow::anyhow!("the {} isn't json: {}", what, e))
+}
+
+/// Splits and decodes a token. A leading `Bearer ` (as copied from an Authorization header) is
+/// ignored.
+pub fn decode(input: &str) -> anyhow::Result<Token> {
+    let input = input.trim();
+    let input = input.strip_prefix("Bearer ").unwrap_or(input).trim();
+    let parts: Vec<&str> = input.split('.').collect();
+    let [header, payload, signature] = parts[..] else {
+        anyhow::bail!(
+            "a jwt has 3 parts separated by dots, but this has {}",
+            parts.len()
+        );
+    };
+    Ok(Token {
+        header: decode_part(header, "header")?,
+        payload: decode_part(payload, "payload")?,
+        message: format!("{}.{}", header, payload),
+        signature: signature.to_string(),
+    })
+}
+
+impl Token {
+    pub fn algorithm(&self) -> Option<&str> {
+        self.header.get("alg")?.as_str()
+    }
+
+    /// Checks the signature. `key` is the shared secret for the HS algorithms, or a PEM public
+    /// key for the RS, PS, ES and EdDSA ones. The algorithm comes from the header, which anyone
+    /// can change, so a PEM key is never used as an HS secret: public keys are public, and a
+    /// token "signed" with one that way is a forgery.
+    pub fn verify(&self, key: &str) -> anyhow::Result<bool> {
+        let name = self
+            .algorithm()
+            .ok_or_else(|| anyhow::anyhow!("the header doesn't say which algorithm was used"))?;
+        let algorithm: Algorithm = name
+            .parse()
+            .map_err(|_| anyhow::anyhow!("can't verify {} tokens", name))?;
+        let pem = key.trim().as_bytes();
+        let invalid =
+            |kind| move |e| anyhow::anyhow!("expected {} public key as pem ({})", kind, e);
+        let key = match algorithm {
+            Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512 => {
+                if key.trim_start().starts_with("-----BEGIN") {
+                    anyhow::bail!(
+                        "{} takes a shared secret, not a pem key, so this token can't be trusted",
+                        name
+                    );
+                }
+                DecodingKey::from_secret(key.as_bytes())
+            }
+            Algorithm::RS256
+            | Algorithm::RS384
+            | Algorithm::RS512
+            | Algorithm::PS256
+            | Algorithm::PS384
+            | Algorithm::PS512 => DecodingKey::from_rsa_pem(pem).map_err(invalid("an rsa"))?,
+            Algorithm::ES256 | Algorithm::ES384 => {
+                DecodingKey::from_ec_pem(pem).map_err(invalid("an ec"))?
+            }
+            Algorithm::EdDSA => DecodingKey::from_ed_pem(pem).map_err(invalid("an ed25519"))?,
+        };
+        Ok(jsonwebtoken::crypto::verify(
+            &self.signature,
+            self.message.as_bytes(),
+            &key,
+            algorithm,
+        )?)
+    }
+
+    /// The `exp`, `nbf` and `iat` claims that are present, as dates.
+    pub fn times(&self) -> Vec<Time> {
+        TIME_CLAIMS
+            .iter()
+            .filter_map(|&(claim, label)| {
+                // NumericDate is allowed to have a fractional part.
+                let seconds = self.payload.get(claim)?.as_f64()?;
+                let date = DateTime::from_timestamp_millis((seconds * 1000.0) as i64)?;
+                Some(Time { claim, label, date })
+            })
+            .collect()
+    }
+
+    pub fn validity(&self, now: DateTime<Utc>) -> Validity {
+        let times = self.times();
+        let date = |claim| times.iter().find(|t| t.claim == claim).map(|t| t.date);
+        if date("exp").is_some_and(|exp| exp <= now) {
+            Validity::Expired
+        } else if date("nbf").is_some_and(|nbf| nbf > now) {
+            Validity::NotYetValid
+        } else {
+            Validity::Valid
+        }
+    }
+}
+
+/// How far `date` is from `now`, e.g. "in 3 hours" or "2 days ago".
+pub fn relative(date: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let seconds = (date - now).num_seconds();
+    let magnitude = seconds.unsigned_abs();
+    let (amount, unit) = [
+        (86400, "day"),
+        (3600, "hour"),
+        (60, "minute"),
+        (1, "second"),
+    ]
+    .into_iter()
+    .map(|(size, unit)| (magnitude / size, unit))
+    .find(|&(amount, _)| amount > 0)
+    .unwrap_or((0, "second"));
+    let plural = if amount == 1 { "" } else { "s" };
+    if seconds >= 0 {
+        format!("in {} {}{}", amount, unit, plural)
+    } else {
+        format!("{} {}{} ago", amount, unit, plural)
+    }
+}
+
+/// Replaces a token with its decoded header and payload.
+pub struct DecodeJwt;
+
+impl Transform for DecodeJwt {
+    fn name(&self) -> &str {
+        "decode jwt"
+    }
+
+    fn description(&self) -> &str {
+        "decode a json web token's header and payload"
+    }
+
+    fn default_key(&self) -> Option<&str> {
+        None
+    }
+
+    fn apply(&self, input: &str) -> anyhow::Result<String> {
+        let token = decode(input)?;
+        Ok(serde_json::to_string_pretty(&json!({
+            "header": token.header,
+            "payload": token.payload,
+        }))?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use jsonwebtoken::{EncodingKey, Header};
+
+    use super::*;
+
+    const PUBLIC_KEY: &str = "-----BEGIN PUBLIC KEY-----
+MCowBQYDK2VwAyEAGb9ECWmEzf6FQbrBZ9w7lshQhqowtrbLDFw4rXAxZuE=
+-----END PUBLIC KEY-----";
+
+    fn token(header: Header, key: &[u8]) -> String {
+        let claims = json!({"sub": "me", "exp": 2_000_000_000, "iat": 1_700_000_000.5});
+        jsonwebtoken::encode(&header, &claims, &EncodingKey::from_secret(key)).unwrap()
+    }
+
+    #[test]
+    fn decodes_and_verifies() {
+        let encoded = token(Header::default(), b"secret");
+        let token = decode(&format!("Bearer {}\n", encoded)).unwrap();
+        assert_eq!(token.algorithm(), Some("HS256"));
+        assert_eq!(token.payload["sub"], "me");
+        assert!(token.verify("secret").unwrap());
+        assert!(!token.verify("wrong").unwrap());
+
+        let decoded = DecodeJwt.apply(&encoded).unwrap();
+        assert!(decoded.contains("\"typ\": \"JWT\""));
+    }
+
+    #[test]
+    fn pem_keys_are_not_hmac_secrets() {
+        // A token "signed" with the public key as an HS256 secret, as in alg confusion attacks.
+        let forged = decode(&token(Header::default(), PUBLIC_KEY.as_bytes())).unwrap();
+        assert!(forged.verify(PUBLIC_KEY).is_err());
+        assert!(forged.verify(&format!("\n{}\n", PUBLIC_KEY)).is_err());
+    }
+
+    #[test]
+    fn rejects_malformed_tokens() {
+        assert!(decode("a.b").is_err());
+        assert!(decode("!!.e30.sig").is_err());
+        assert!(decode("bm90IGpzb24.e30.sig").is_err());
+        let none = decode("eyJhbGciOiJub25lIn0.e30.").unwrap();
+        assert!(none.verify("secret").is_err());
+    }
+
+    #[test]
+    fn reads_time_claims() {
+        let token = decode(&token(Header::default(), b"secret")).unwrap();
+        let times = token.times();
+        let claims: Vec<&str> = times.iter().map(|t| t.claim).collect();
+        assert_eq!(claims, ["exp", "iat"]);
+        assert_eq!(times[1].date.timestamp_millis(), 1_700_000_000_500);
+
+        let before = DateTime::from_timestamp(1_999_999_999, 0).unwrap();
+        assert!(matches!(token.validity(before), Validity::Valid));
+        let after = DateTime::from_timestamp(2_000_000_000, 0).unwrap();
+        assert!(matches!(token.validity(after), Validity::Expired));
+    }
+
+    #[test]
+    fn relative_times() {
+        let now = DateTime::from_timestamp(1_000_000, 0).unwrap();
+        let at = |seconds: i64| DateTime::from_timestamp(1_000_000 + seconds, 0).unwrap();
+        assert_eq!(relative(at(3 * 3600 + 5), now), "in 3 hours");
+        assert_eq!(relative(at(-86400), now), "1 day ago");
+        assert_eq!(relative(now, now), "in 0 seconds");
+    }
+}
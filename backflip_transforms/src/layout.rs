@@ -0,0 +1,470 @@
+use std::sync::OnceLock;
+
+use ::regex::Regex;
+
+use crate::lines::map_lines;
+use crate::{Setting, Transform};
+
+fn prefix_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX
+        .get_or_init(|| Regex::new(r"^[ \t]*(?:(?://[/!]?|#+|>(?:[ \t]*>)*)(?:[ \t]|$))?").unwrap())
+}
+
+fn list_item_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| Regex::new(r"^(?:[-*+]|[0-9]+[.)])[ \t]").unwrap())
+}
+
+/// Splits a line into its indentation and comment marker (`//`, `///`, `//!`, `#` or `>`), and
+/// the text after them.
+fn split_prefix(line: &str) -> (&str, &str) {
+    line.split_at(prefix_regex().find(line).map_or(0, |m| m.end()))
+}
+
+/// Fills `words` into lines of at most `width` characters, prefix included, or into a single
+/// line if there's no width. The first line gets `prefix` and the rest get `hanging`.
+fn fill(prefix: &str, hanging: &str, words: &[&str], width: Option<usize>) -> Vec<String> {
+    let mut lines = vec![];
+    let mut line = prefix.to_string();
+    let mut empty = true;
+    for word in words {
+        let fits = match width {
+            Some(width) => line.chars().count() + 1 + word.chars().count() <= width,
+            None => true,
+        };
+        if !empty && !fits {
+            lines.push(line);
+            line = hanging.to_string();
+            empty = true;
+        }
+        if !empty {
+            line.push(' ');
+        }
+        line.push_str(word);
+        empty = false;
+    }
+    lines.push(line);
+    lines
+}
+
+/// The marker of a Markdown code fence, if the line opens or closes one.
+fn fence(content: &str) -> Option<&'static str> {
+    let content = content.trim_start();
+    ["```", "~~~"]
+        .into_iter()
+        .find(|marker| content.starts_with(marker))
+}
+
+/// Whether a prefix with no comment marker indents its line enough to make it a Markdown code
+/// block, counting a tab as four spaces.
+fn indented_code(prefix: &str) -> bool {
+    let columns: usize = prefix
+        .chars()
+        .map(|c| match c {
+            ' ' => 1,
+            '\t' => 4,
+            _ => 0,
+        })
+        .sum();
+    prefix.trim().is_empty() && columns >= 4
+}
+
+/// Joins each paragraph into one run of words and fills it back out to `width`. A paragraph is
+/// a run of lines with the same comment prefix, ended by a blank line, a list item, a code fence
+/// or a line indented further. Fenced code, lines indented past their comment prefix and plain
+/// lines indented four or more spaces are code, and are left alone.
+fn reflow(input: &str, width: Option<usize>) -> String {
+    map_lines(input, |lines| {
+        let mut out = vec![];
+        let mut open_fence = None;
+        let mut i = 0;
+        while i < lines.len() {
+            let (prefix, content) = split_prefix(lines[i]);
+            i += 1;
+            if let Some(marker) = open_fence {
+                if fence(content) == Some(marker) {
+                    open_fence = None;
+                }
+                out.push(lines[i - 1].to_string());
+                continue;
+            }
+            open_fence = fence(content);
+            // Blank lines end paragraphs, and fences and indented lines are code.
+            if open_fence.is_some()
+                || content.trim().is_empty()
+                || content.starts_with([' ', '\t'])
+                || indented_code(prefix)
+            {
+                out.push(lines[i - 1].to_string());
+                continue;
+            }
+            // List items wrap with a hanging indent, under the text after the marker.
+            let hanging = format!(
+                "{}{}",
+                prefix,
+                " ".repeat(list_item_regex().find(content).map_or(0, |m| m.len()))
+            );
+            let mut words: Vec<&str> = content.split_whitespace().collect();
+            while i < lines.len() {
+                let Some(rest) = lines[i].strip_prefix(hanging.as_str()) else {
+                    break;
+                };
+                let continues = !rest.trim().is_empty()
+                    && split_prefix(rest).0.is_empty()
+                    && !list_item_regex().is_match(rest)
+                    && fence(rest).is_none();
+                if !continues {
+                    break;
+                }
+                words.extend(rest.split_whitespace());
+                i += 1;
+            }
+            out.extend(fill(prefix, &hanging, &words, width));
+        }
+        out
+    })
+}
+
+/// Hard wraps paragraphs, keeping each line's indentation and comment prefix.
+pub struct Wrap {
+    pub column: usize,
+}
+
+impl Default for Wrap {
+    fn default() -> Self {
+        Wrap { column: 80 }
+    }
+}
+
+impl Transform for Wrap {
+    fn name(&self) -> &str {
+        "wrap text"
+    }
+
+    fn description(&self) -> &str {
+        "hard wrap paragraphs and comments to a column"
+    }
+
+    fn default_key(&self) -> Option<&str> {
+        None
+    }
+
+    fn apply(&self, input: &str) -> anyhow::Result<String> {
+        Ok(reflow(input, Some(self.column)))
+    }
+
+    fn settings(&mut self) -> Vec<Setting<'_>> {
+        vec![Setting::Number {
+            label: "column",
+            value: &mut self.column,
+            range: 10..=200,
+        }]
+    }
+}
+
+/// The reverse of `Wrap`: puts each paragraph back on one line.
+pub struct Unwrap;
+
+impl Transform for Unwrap {
+    fn name(&self) -> &str {
+        "unwrap text"
+    }
+
+    fn description(&self) -> &str {
+        "join hard wrapped paragraphs into single lines"
+    }
+
+    fn default_key(&self) -> Option<&str> {
+        None
+    }
+
+    fn apply(&self, input: &str) -> anyhow::Result<String> {
+        Ok(reflow(input, None))
+    }
+}
+
+/// Expands tabs to the next tab stop, so text after a tab still lines up.
+pub struct TabsToSpaces {
+    pub width: usize,
+}
+
+impl Default for TabsToSpaces {
+    fn default() -> Self {
+        TabsToSpaces { width: 4 }
+    }
+}
+
+impl Transform for TabsToSpaces {
+    fn name(&self) -> &str {
+        "tabs to spaces"
+    }
+
+    fn description(&self) -> &str {
+        "expand tabs to spaces"
+    }
+
+    fn default_key(&self) -> Option<&str> {
+        None
+    }
+
+    fn apply(&self, input: &str) -> anyhow::Result<String> {
+        Ok(map_lines(input, |lines| {
+            lines
+                .into_iter()
+                .map(|line| {
+                    let mut out = String::with_capacity(line.len());
+                    let mut column = 0;
+                    for c in line.chars() {
+                        if c == '\t' {
+                            let spaces = self.width - column % self.width;
+                            out.push_str(&" ".repeat(spaces));
+                            column += spaces;
+                        } else {
+                            out.push(c);
+                            column += 1;
+                        }
+                    }
+                    out
+                })
+                .collect()
+        }))
+    }
+
+    fn settings(&mut self) -> Vec<Setting<'_>> {
+        vec![Setting::Number {
+            label: "tab width",
+            value: &mut self.width,
+            range: 1..=16,
+        }]
+    }
+}
+
+/// Turns indentation into tabs. Only leading whitespace changes, since spaces used to line up
+/// text in the middle of a line would stop lining up with a different tab width.
+pub struct SpacesToTabs {
+    pub width: usize,
+}
+
+impl Default for SpacesToTabs {
+    fn default() -> Self {
+        SpacesToTabs { width: 4 }
+    }
+}
+
+impl Transform for SpacesToTabs {
+    fn name(&self) -> &str {
+        "spaces to tabs"
+    }
+
+    fn description(&self) -> &str {
+        "indent with tabs instead of spaces"
+    }
+
+    fn default_key(&self) -> Option<&str> {
+        None
+    }
+
+    fn apply(&self, input: &str) -> anyhow::Result<String> {
+        Ok(map_lines(input, |lines| {
+            lines
+                .into_iter()
+                .map(|line| {
+                    let content = line.trim_start_matches([' ', '\t']);
+                    let indent = &line[..line.len() - content.len()];
+                    let columns = indent.chars().fold(0, |column, c| match c {
+                        '\t' => column + self.width - column % self.width,
+                        _ => column + 1,
+                    });
+                    format!(
+                        "{}{}{}",
+                        "\t".repeat(columns / self.width),
+                        " ".repeat(columns % self.width),
+                        content
+                    )
+                })
+                .collect()
+        }))
+    }
+
+    fn settings(&mut self) -> Vec<Setting<'_>> {
+        vec![Setting::Number {
+            label: "tab width",
+            value: &mut self.width,
+            range: 1..=16,
+        }]
+    }
+}
+
+/// Removes the indentation every line has in common, like code copied from the middle of a
+/// function. Blank lines don't count.
+pub struct Dedent;
+
+impl Transform for Dedent {
+    fn name(&self) -> &str {
+        "dedent"
+    }
+
+    fn description(&self) -> &str {
+        "remove the indentation all lines share"
+    }
+
+    fn default_key(&self) -> Option<&str> {
+        None
+    }
+
+    fn apply(&self, input: &str) -> anyhow::Result<String> {
+        Ok(map_lines(input, |lines| {
+            let indent = |line: &str| line.len() - line.trim_start_matches([' ', '\t']).len();
+            // Compare the indentation itself rather than its width, so a tab never
+            // counts as matching spaces.
+            let common = lines
+                .iter()
+                .filter(|line| !line.trim().is_empty())
+                .map(|line| &line[..indent(line)])
+                .reduce(|common, other| {
+                    let shared = common
+                        .bytes()
+                        .zip(other.bytes())
+                        .take_while(|(a, b)| a == b)
+                        .count();
+                    &common[..shared]
+                })
+                .unwrap_or("");
+            lines
+                .into_iter()
+                .map(|line| match line.strip_prefix(common) {
+                    Some(rest) => rest.to_string(),
+                    // A blank line with less indentation than the rest.
+                    None => String::new(),
+                })
+                .collect()
+        }))
+    }
+}
+
+/// Indents every line that isn't blank by a number of spaces.
+pub struct Indent {
+    pub spaces: usize,
+}
+
+impl Default for Indent {
+    fn default() -> Self {
+        Indent { spaces: 4 }
+    }
+}
+
+impl Transform for Indent {
+    fn name(&self) -> &str {
+        "indent"
+    }
+
+    fn description(&self) -> &str {
+        "indent lines by a number of spaces"
+    }
+
+    fn default_key(&self) -> Option<&str> {
+        None
+    }
+
+    fn apply(&self, input: &str) -> anyhow::Result<String> {
+        let indent = " ".repeat(self.spaces);
+        Ok(map_lines(input, |lines| {
+            lines
+                .into_iter()
+                .map(|line| {
+                    if line.trim().is_empty() {
+                        line.to_string()
+                    } else {
+                        format!("{}{}", indent, line)
+                    }
+                })
+                .collect()
+        }))
+    }
+
+    fn settings(&mut self) -> Vec<Setting<'_>> {
+        vec![Setting::Number {
+            label: "spaces",
+            value: &mut self.spaces,
+            range: 1..=32,
+        }]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wraps_comments_and_lists() {
+        let wrap = Wrap { column: 20 };
+        assert_eq!(
+            wrap.apply("/// one two three four five six").unwrap(),
+            "/// one two three\n/// four five six"
+        );
+        assert_eq!(
+            wrap.apply("- one two three four five\n- six").unwrap(),
+            "- one two three four\n  five\n- six"
+        );
+        assert_eq!(
+            wrap.apply("> > quoted text that wraps").unwrap(),
+            "> > quoted text that\n> > wraps"
+        );
+    }
+
+    #[test]
+    fn unwrap_round_trips() {
+        let text = "# a long comment that goes on\n\n1. first item with words\n2. second";
+        let wrapped = Wrap { column: 12 }.apply(text).unwrap();
+        assert_ne!(wrapped, text);
+        assert_eq!(Unwrap.apply(&wrapped).unwrap(), text);
+    }
+
+    #[test]
+    fn leaves_fenced_code_alone() {
+        let text = "Some words\nhere.\n```rust\nlet a = 1;\nlet b = 2;\n```\nMore\nwords.";
+        assert_eq!(
+            Unwrap.apply(text).unwrap(),
+            "Some words here.\n```rust\nlet a = 1;\nlet b = 2;\n```\nMore words."
+        );
+        let doc = "/// Text\n/// ~~~\n/// a\n/// ```\n/// b\n/// ~~~\n/// end";
+        assert_eq!(Unwrap.apply(doc).unwrap(), doc);
+    }
+
+    #[test]
+    fn leaves_indented_code_alone() {
+        let code = "    let a = 1;\n    let b = 2;\n\tfoo();\n\tbar();";
+        assert_eq!(Unwrap.apply(code).unwrap(), code);
+        assert_eq!(
+            Unwrap
+                .apply("    // a comment\n    // that continues")
+                .unwrap(),
+            "    // a comment that continues"
+        );
+        assert_eq!(
+            Unwrap.apply("///     code();\n///     more();").unwrap(),
+            "///     code();\n///     more();"
+        );
+    }
+
+    #[test]
+    fn converts_tabs_and_spaces() {
+        assert_eq!(
+            TabsToSpaces { width: 4 }.apply("a\tb\n\tc").unwrap(),
+            "a   b\n    c"
+        );
+        assert_eq!(
+            SpacesToTabs { width: 4 }.apply("      x  y\n \tz").unwrap(),
+            "\t  x  y\n\tz"
+        );
+    }
+
+    #[test]
+    fn indents_and_dedents() {
+        let text = "    a\n\n      b\n    c";
+        assert_eq!(Dedent.apply(text).unwrap(), "a\n\n  b\nc");
+        assert_eq!(Dedent.apply("\ta\n    b").unwrap(), "\ta\n    b");
+        assert_eq!(Indent { spaces: 2 }.apply("a\n\nb").unwrap(), "  a\n\n  b");
+    }
+}
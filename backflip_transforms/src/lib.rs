@@ -0,0 +1,311 @@
+pub mod base64;
+pub mod case;
+pub mod formats;
+pub mod hash;
+pub mod hex;
+pub mod html;
+pub mod integer;
+pub mod json;
+pub mod jwt;
+pub mod layout;
+pub mod lines;
+pub mod list;
+pub mod literal;
+pub mod path;
+pub mod query;
+pub mod replace;
+pub mod rust_structs;
+pub mod table;
+pub mod timestamp;
+pub mod unicode;
+pub mod url;
+pub mod uuid;
+pub mod xml;
+
+use std::ops::RangeInclusive;
+use std::path::PathBuf;
+
+/// Keys the session's text view handles itself in command mode, so transforms can't be bound to
+/// them. Names are as understood by `egui::Key::from_name`.
+pub const RESERVED_KEYS: &[&str] = &["I", "F", "L", "Q", "J", "G", "W", "Enter", "Escape"];
+
+/// Work a transform hands back to run on a worker thread, giving the new buffer.
+pub type Job = Box<dyn FnOnce() -> anyhow::Result<String> + Send>;
+
+/// A text transformation that can be applied to the contents of a session.
+pub trait Transform {
+    /// Short lowercase label, e.g. "serialize json".
+    fn name(&self) -> &str;
+    /// One line of help text shown in the session window.
+    fn description(&self) -> &str;
+    /// Name of the key that runs this transform (as understood by `egui::Key::from_name`),
+    /// or `None` if it can only be run from the transform list.
+    fn default_key(&self) -> Option<&str>;
+    fn apply(&self, input: &str) -> anyhow::Result<String>;
+    /// Called instead of `apply` when the clipboard held a list of files rather than text.
+    /// Reading files can take a while, so this returns the work to do and the session runs it
+    /// off the UI thread. `None` means the transform only works on text, and gets the listing of
+    /// paths instead.
+    fn apply_files(&self, _files: &[PathBuf]) -> Option<Job> {
+        None
+    }
+    /// For transforms that decode to raw bytes, which might not be UTF-8. The session calls
+    /// this instead of `apply` so it can show such bytes in a binary view; `apply` should still
+    /// give something readable for them, like a hex dump.
+    fn apply_bytes(&self, _input: &str) -> Option<anyhow::Result<Vec<u8>>> {
+        None
+    }
+    /// Options the session window should offer for this transform.
+    fn settings(&mut self) -> Vec<Setting<'_>> {
+        vec![]
+    }
+}
+
+/// A user-adjustable option on a transform. This only describes the option; the session window
+/// decides which widget to draw for it.
+pub enum Setting<'a> {
+    Number {
+        label: &'static str,
+        value: &'a mut usize,
+        range: RangeInclusive<usize>,
+    },
+    Toggle {
+        label: &'static str,
+        value: &'a mut bool,
+    },
+    /// `value` is an index into `options`.
+    Choice {
+        label: &'static str,
+        value: &'a mut usize,
+        options: &'static [&'static str],
+    },
+    Text {
+        label: &'static str,
+        value: &'a mut String,
+    },
+}
+
+/// An ordered collection of transforms. The session UI iterates this to bind keys and render
+/// its help text, so registering a transform here is all that's needed to expose it.
+pub struct Registry {
+    transforms: Vec<Box<dyn Transform>>,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Registry { transforms: vec![] }
+    }
+
+    /// All transforms that ship with backflip.
+    pub fn with_builtins() -> Self {
+        let mut registry = Registry::new();
+        registry.register(json::SerializeJson);
+        registry.register(json::DeserializeJson);
+        registry.register(literal::EscapeString::default());
+        registry.register(literal::UnescapeString::default());
+        registry.register(json::PrettyJson::default());
+        registry.register(json::MinifyJson);
+        registry.register(json::CanonicalJson);
+        registry.register(json::StripJsonNulls);
+        registry.register(formats::ConvertTo(formats::Format::Json));
+        registry.register(formats::ConvertTo(formats::Format::Yaml));
+        registry.register(formats::ConvertTo(formats::Format::Toml));
+        registry.register(formats::ConvertTo(formats::Format::Ron));
+        registry.register(formats::ConvertTo(formats::Format::Xml));
+        registry.register(xml::PrettyXml::default());
+        registry.register(xml::MinifyXml);
+        registry.register(html::EncodeEntities { non_ascii: false });
+        registry.register(html::DecodeEntities);
+        registry.register(rust_structs::JsonToRustStructs::default());
+        registry.register(table::TableToMarkdown);
+        registry.register(table::TableToJson::default());
+        registry.register(table::TableToSql::default());
+        registry.register(table::JsonToCsv::default());
+        registry.register(table::MarkdownToTsv);
+        registry.register(jwt::DecodeJwt);
+        registry.register(path::FlipPath::default());
+        registry.register(path::ConvertPath::new(path::Flavor::Windows));
+        registry.register(path::ConvertPath::new(path::Flavor::Mixed));
+        registry.register(path::ConvertPath::new(path::Flavor::Wsl));
+        registry.register(path::ConvertPath::new(path::Flavor::Msys));
+        registry.register(path::ConvertPath::new(path::Flavor::FileUri));
+        registry.register(case::ConvertCase::new(case::Case::Snake));
+        registry.register(case::ConvertCase::new(case::Case::Camel));
+        registry.register(case::ConvertCase::new(case::Case::Pascal));
+        registry.register(case::ConvertCase::new(case::Case::Kebab));
+        registry.register(case::ConvertCase::new(case::Case::ScreamingSnake));
+        registry.register(case::ConvertCase::new(case::Case::Title));
+        registry.register(lines::SortLines::default());
+        registry.register(lines::UniqueLines::default());
+        registry.register(lines::ReverseLines);
+        registry.register(lines::NumberLines::default());
+        registry.register(lines::RemoveBlankLines);
+        registry.register(lines::ShuffleLines::default());
+        registry.register(list::JoinList::default());
+        registry.register(list::SplitList);
+        registry.register(layout::Wrap::default());
+        registry.register(layout::Unwrap);
+        registry.register(layout::TabsToSpaces::default());
+        registry.register(layout::SpacesToTabs::default());
+        registry.register(layout::Dedent);
+        registry.register(layout::Indent::default());
+        registry.register(base64::Base64Encode(base64::Variant::Standard));
+        registry.register(base64::Base64Encode(base64::Variant::StandardNoPad));
+        registry.register(base64::Base64Encode(base64::Variant::UrlSafe));
+        registry.register(base64::Base64Encode(base64::Variant::UrlSafeNoPad));
+        registry.register(base64::Base64Decode);
+        registry.register(unicode::Normalize(unicode::Form::Nfc));
+        registry.register(unicode::Normalize(unicode::Form::Nfd));
+        registry.register(unicode::Normalize(unicode::Form::Nfkc));
+        registry.register(unicode::Normalize(unicode::Form::Nfkd));
+        registry.register(unicode::StripInvisibles {
+            keep_controls: false,
+        });
+        registry.register(hex::HexDump::default());
+        registry.register(hex::HexDecode);
+        registry.register(integer::ConvertBase::new(integer::Base::Hex));
+        registry.register(url::PercentEncode(url::Rules::Component));
+        registry.register(url::PercentEncode(url::Rules::Path));
+        registry.register(url::PercentEncode(url::Rules::Form));
+        registry.register(url::PercentDecode { form: false });
+        registry.register(url::PercentDecode { form: true });
+        registry.register(hash::Hash::new(hash::Algorithm::Md5));
+        registry.register(hash::Hash::new(hash::Algorithm::Sha1));
+        registry.register(hash::Hash::new(hash::Algorithm::Sha256));
+        registry.register(hash::Hash::new(hash::Algorithm::Sha512));
+        registry.register(hash::Hash::new(hash::Algorithm::Blake3));
+        registry.register(hash::Hash::new(hash::Algorithm::Crc32));
+        registry.register(timestamp::TimestampsToDates { local: false });
+        registry.register(timestamp::DatesToTimestamps::default());
+        registry.register(uuid::GenerateUuid::default());
+        registry.register(uuid::FormatUuid::default());
+        registry
+    }
+
+    pub fn register<T: Transform + 'static>(&mut self, transform: T) {
+        self.transforms.push(Box::new(transform));
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &dyn Transform> {
+        self.transforms.iter().map(|t| t.as_ref())
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut (dyn Transform + 'static)> {
+        self.transforms.iter_mut().map(|t| t.as_mut())
+    }
+
+    pub fn get(&self, name: &str) -> Option<&dyn Transform> {
+        self.iter().find(|t| t.name() == name)
+    }
+
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut (dyn Transform + 'static)> {
+        self.iter_mut().find(|t| t.name() == name)
+    }
+}
+
+impl Default for Registry {
+    fn default() -> Self {
+        Registry::with_builtins()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    #[test]
+    fn builtin_names_are_unique() {
+        let registry = Registry::with_builtins();
+        let mut names = HashSet::new();
+        for transform in registry.iter() {
+            assert!(names.insert(transform.name()), "{}", transform.name());
+            assert_eq!(transform.name(), transform.name().to_lowercase());
+        }
+    }
+
+    #[test]
+    fn builtin_keys_are_unique_and_not_reserved() {
+        let registry = Registry::with_builtins();
+        let mut keys = HashSet::new();
+        for key in registry.iter().filter_map(|t| t.default_key()) {
+            assert!(!RESERVED_KEYS.contains(&key), "{} is reserved", key);
+            assert!(keys.insert(key), "{} is bound twice", key);
+        }
+    }
+
+    #[test]
+    fn lookup_by_name() {
+        let mut registry = Registry::with_builtins();
+        assert_eq!(
+            registry.get("minify json").unwrap().apply("[ 1 ]").unwrap(),
+            "[1]"
+        );
+        assert!(registry.get("no such transform").is_none());
+        let sort = registry.get_mut("sort lines").unwrap();
+        for setting in sort.settings() {
+            if let Setting::Toggle { value, .. } = setting {
+                *value = true;
+            }
+        }
+        assert_eq!(
+            registry.get("sort lines").unwrap().apply("a\nb").unwrap(),
+            "b\na"
+        );
+    }
+
+    #[test]
+    fn registered_transforms_come_last() {
+        let mut registry = Registry::new();
+        assert_eq!(registry.iter().count(), 0);
+        registry.register(json::MinifyJson);
+        registry.register(json::SerializeJson);
+        let names: Vec<&str> = registry.iter().map(|t| t.name()).collect();
+        assert_eq!(names, ["minify json", "serialize json"]);
+    }
+
+    #[test]
+    fn builtins_never_panic() {
+        let inputs = [
+            "",
+            "日本語\\ファイル",
+            "2024-01-01T00:00:00+०५:००",
+            "\u{FEFF}\r\n\t",
+            r#"{"a": [1, 2.5, null, "x"], "b": {"c": true}}"#,
+            "-",
+            "0x",
+            "[",
+            "<a>",
+            "a,b\n\"unterminated",
+        ];
+        let mut registry = Registry::with_builtins();
+        // Once with the default settings, then with every toggle on and every choice at its last
+        // option.
+        for pass in 0..2 {
+            for transform in registry.iter_mut() {
+                if pass == 1 {
+                    for setting in transform.settings() {
+                        match setting {
+                            Setting::Toggle { value, .. } => *value = true,
+                            Setting::Choice { value, options, .. } => *value = options.len() - 1,
+                            _ => (),
+                        }
+                    }
+                }
+                for input in inputs {
+                    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                        let _ = transform.apply(input);
+                        let _ = transform.apply_bytes(input);
+                    }));
+                    assert!(
+                        result.is_ok(),
+                        "{} panicked on {:?}",
+                        transform.name(),
+                        input
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,358 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use crate::{Setting, Transform};
+
+/// Runs `f` over the lines of `input`, keeping its line endings (CRLF or LF) and whether it
+/// ended with a newline.
+pub(crate) fn map_lines(input: &str, f: impl FnOnce(Vec<&str>) -> Vec<String>) -> String {
+    let newline = if input.contains("\r\n") { "\r\n" } else { "\n" };
+    let trailing = input.ends_with('\n');
+    let mut out = f(input.lines().collect()).join(newline);
+    if trailing {
+        out.push_str(newline);
+    }
+    out
+}
+
+/// Compares runs of digits by their value, so `file2` sorts before `file10`. Everything else is
+/// compared case-insensitively, with case only breaking ties.
+pub fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let mut a_chars = a.chars().peekable();
+    let mut b_chars = b.chars().peekable();
+    loop {
+        match (a_chars.peek().copied(), b_chars.peek().copied()) {
+            (None, None) => return a.cmp(b),
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let mut x_digits = String::new();
+                while let Some(c) = a_chars.next_if(char::is_ascii_digit) {
+                    x_digits.push(c);
+                }
+                let mut y_digits = String::new();
+                while let Some(c) = b_chars.next_if(char::is_ascii_digit) {
+                    y_digits.push(c);
+                }
+                let x_value = x_digits.trim_start_matches('0');
+                let y_value = y_digits.trim_start_matches('0');
+                let ordering = x_value
+                    .len()
+                    .cmp(&y_value.len())
+                    .then_with(|| x_value.cmp(y_value));
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+            (Some(x), Some(y)) => {
+                let ordering = x.to_lowercase().cmp(y.to_lowercase());
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+                a_chars.next();
+                b_chars.next();
+            }
+        }
+    }
+}
+
+const SORT_ORDERS: &[&str] = &["natural", "locale", "byte value"];
+
+#[derive(Default)]
+pub struct SortLines {
+    /// An index into `SORT_ORDERS`.
+    pub order: usize,
+    pub descending: bool,
+}
+
+impl Transform for SortLines {
+    fn name(&self) -> &str {
+        "sort lines"
+    }
+
+    fn description(&self) -> &str {
+        "sort lines naturally, by locale or by byte value"
+    }
+
+    fn default_key(&self) -> Option<&str> {
+        Some("O")
+    }
+
+    fn apply(&self, input: &str) -> anyhow::Result<String> {
+        Ok(map_lines(input, |mut lines| {
+            match self.order {
+                0 => lines.sort_by(|a, b| natural_cmp(a, b)),
+                1 => {
+                    // The unicode collation algorithm's root order, which is what most locales
+                    // agree on: accents and case only matter when everything else is equal.
+                    let mut collator = feruca::Collator::default();
+                    lines.sort_by(|a, b| collator.collate(*a, *b));
+                }
+                _ => lines.sort(),
+            }
+            if self.descending {
+                lines.reverse();
+            }
+            lines.into_iter().map(str::to_string).collect()
+        }))
+    }
+
+    fn settings(&mut self) -> Vec<Setting<'_>> {
+        vec![
+            Setting::Choice {
+                label: "order",
+                value: &mut self.order,
+                options: SORT_ORDERS,
+            },
+            Setting::Toggle {
+                label: "descending",
+                value: &mut self.descending,
+            },
+        ]
+    }
+}
+
+/// Removes repeated lines, keeping the first of each in place. Unlike `uniq`, the duplicates
+/// don't need to be next to each other.
+#[derive(Default)]
+pub struct UniqueLines {
+    /// Prefix each line with how many times it appeared, like `uniq -c`.
+    pub count: bool,
+}
+
+impl Transform for UniqueLines {
+    fn name(&self) -> &str {
+        "unique lines"
+    }
+
+    fn description(&self) -> &str {
+        "remove duplicate lines"
+    }
+
+    fn default_key(&self) -> Option<&str> {
+        None
+    }
+
+    fn apply(&self, input: &str) -> anyhow::Result<String> {
+        Ok(map_lines(input, |lines| {
+            let mut counts: HashMap<&str, usize> = HashMap::new();
+            let mut unique = vec![];
+            for line in lines {
+                let count = counts.entry(line).or_default();
+                if *count == 0 {
+                    unique.push(line);
+                }
+                *count += 1;
+            }
+            if !self.count {
+                return unique.into_iter().map(str::to_string).collect();
+            }
+            let width = counts.values().max().unwrap_or(&0).to_string().len();
+            unique
+                .into_iter()
+                .map(|line| format!("{:>width$} {}", counts[line], line, width = width))
+                .collect()
+        }))
+    }
+
+    fn settings(&mut self) -> Vec<Setting<'_>> {
+        vec![Setting::Toggle {
+            label: "count duplicates",
+            value: &mut self.count,
+        }]
+    }
+}
+
+pub struct ReverseLines;
+
+impl Transform for ReverseLines {
+    fn name(&self) -> &str {
+        "reverse lines"
+    }
+
+    fn description(&self) -> &str {
+        "reverse the order of lines"
+    }
+
+    fn default_key(&self) -> Option<&str> {
+        Some("R")
+    }
+
+    fn apply(&self, input: &str) -> anyhow::Result<String> {
+        Ok(map_lines(input, |lines| {
+            lines.into_iter().rev().map(str::to_string).collect()
+        }))
+    }
+}
+
+pub struct NumberLines {
+    pub start: usize,
+}
+
+impl Default for NumberLines {
+    fn default() -> Self {
+        NumberLines { start: 1 }
+    }
+}
+
+impl Transform for NumberLines {
+    fn name(&self) -> &str {
+        "number lines"
+    }
+
+    fn description(&self) -> &str {
+        "prefix each line with its line number"
+    }
+
+    fn default_key(&self) -> Option<&str> {
+        None
+    }
+
+    fn apply(&self, input: &str) -> anyhow::Result<String> {
+        Ok(map_lines(input, |lines| {
+            let last = self.start + lines.len().saturating_sub(1);
+            let width = last.to_string().len();
+            lines
+                .into_iter()
+                .enumerate()
+                .map(|(i, line)| format!("{:>width$} {}", self.start + i, line, width = width))
+                .collect()
+        }))
+    }
+
+    fn settings(&mut self) -> Vec<Setting<'_>> {
+        vec![Setting::Number {
+            label: "start at",
+            value: &mut self.start,
+            range: 0..=1_000_000,
+        }]
+    }
+}
+
+/// Lines with only whitespace on them count as blank.
+pub struct RemoveBlankLines;
+
+impl Transform for RemoveBlankLines {
+    fn name(&self) -> &str {
+        "remove blank lines"
+    }
+
+    fn description(&self) -> &str {
+        "remove empty and whitespace-only lines"
+    }
+
+    fn default_key(&self) -> Option<&str> {
+        None
+    }
+
+    fn apply(&self, input: &str) -> anyhow::Result<String> {
+        Ok(map_lines(input, |lines| {
+            lines
+                .into_iter()
+                .filter(|line| !line.trim().is_empty())
+                .map(str::to_string)
+                .collect()
+        }))
+    }
+}
+
+/// Shuffles deterministically, so the same seed always gives the same order.
+#[derive(Default)]
+pub struct ShuffleLines {
+    pub seed: usize,
+}
+
+impl Transform for ShuffleLines {
+    fn name(&self) -> &str {
+        "shuffle lines"
+    }
+
+    fn description(&self) -> &str {
+        "shuffle lines into a random order"
+    }
+
+    fn default_key(&self) -> Option<&str> {
+        None
+    }
+
+    fn apply(&self, input: &str) -> anyhow::Result<String> {
+        Ok(map_lines(input, |mut lines| {
+            fastrand::Rng::with_seed(self.seed as u64).shuffle(&mut lines);
+            lines.into_iter().map(str::to_string).collect()
+        }))
+    }
+
+    fn settings(&mut self) -> Vec<Setting<'_>> {
+        vec![Setting::Number {
+            label: "seed",
+            value: &mut self.seed,
+            range: 0..=u32::MAX as usize,
+        }]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_line_endings() {
+        let reversed = ReverseLines.apply("a\r\nb\r\n").unwrap();
+        assert_eq!(reversed, "b\r\na\r\n");
+        assert_eq!(ReverseLines.apply("a\nb").unwrap(), "b\na");
+        assert_eq!(ReverseLines.apply("").unwrap(), "");
+    }
+
+    #[test]
+    fn natural_order() {
+        let mut names = ["file10", "File2", "file2", "file01", "file1b"];
+        names.sort_by(|a, b| natural_cmp(a, b));
+        assert_eq!(names, ["file01", "file1b", "File2", "file2", "file10"]);
+        assert_eq!(natural_cmp("a007", "a7"), "a007".cmp("a7"));
+    }
+
+    #[test]
+    fn sorts_each_order() {
+        let input = "b\nÉ\na10\nA\na9\n";
+        let mut sort = SortLines::default();
+        assert_eq!(sort.apply(input).unwrap(), "A\na9\na10\nb\nÉ\n");
+        sort.order = 1;
+        assert_eq!(sort.apply(input).unwrap(), "A\na10\na9\nb\nÉ\n");
+        sort.order = 2;
+        sort.descending = true;
+        assert_eq!(sort.apply(input).unwrap(), "É\nb\na9\na10\nA\n");
+    }
+
+    #[test]
+    fn unique_keeps_first_occurrences() {
+        let input = "b\na\nb\nb\n\na";
+        assert_eq!(UniqueLines::default().apply(input).unwrap(), "b\na\n");
+        let counted = UniqueLines { count: true }.apply(input).unwrap();
+        assert_eq!(counted, "3 b\n2 a\n1 ");
+    }
+
+    #[test]
+    fn numbers_line_up() {
+        let input = "x\n".repeat(10);
+        let numbered = NumberLines::default().apply(&input).unwrap();
+        assert!(numbered.starts_with(" 1 x\n 2 x\n"));
+        assert!(numbered.ends_with("\n10 x\n"));
+        assert_eq!(NumberLines { start: 0 }.apply("x").unwrap(), "0 x");
+    }
+
+    #[test]
+    fn removes_blank_lines() {
+        assert_eq!(RemoveBlankLines.apply("a\n \t\n\nb\n").unwrap(), "a\nb\n");
+    }
+
+    #[test]
+    fn shuffle_is_a_seeded_permutation() {
+        let input = "1\n2\n3\n4\n5\n6\n7\n8";
+        let shuffle = ShuffleLines { seed: 7 };
+        let shuffled = shuffle.apply(input).unwrap();
+        assert_eq!(shuffled, shuffle.apply(input).unwrap());
+        let mut sorted: Vec<&str> = shuffled.lines().collect();
+        sorted.sort();
+        assert_eq!(sorted.join("\n"), input);
+    }
+}
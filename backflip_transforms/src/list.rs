@@ -0,0 +1,377 @@
+use std::collections::HashSet;
+
+use crate::{Setting, Transform};
+
+const SPLITS: &[&str] = &["lines", "commas", "tabs", "whitespace", "delimiter"];
+
+const QUOTES: &[&str] = &["none", "single", "double", "backtick"];
+
+const WRAPPERS: &[&str] = &["none", "( )", "[ ]", "{ }", "vec![ ]"];
+
+/// Lets separators typed into a one line text field contain newlines and tabs.
+fn control_escapes(text: &str) -> String {
+    text.replace("\\n", "\n").replace("\\t", "\t")
+}
+
+/// Escapes for a double quoted string the way JSON does, which Rust and most C-like languages
+/// also accept.
+fn escape_double(item: &str) -> String {
+    let mut out = String::with_capacity(item.len());
+    for c in item.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '"' => out.push_str("\\\""),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            '\r' => out.push_str("\\r"),
+            c if c.is_control() => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+fn quote(item: &str, style: usize) -> String {
+    match QUOTES[style] {
+        // Doubled, the way SQL escapes quotes in strings.
+        "single" => format!("'{}'", item.replace('\'', "''")),
+        // Backslash escaped as in JSON, which suits Rust too apart from the rarer control
+        // characters.
+        "double" => format!("\"{}\"", escape_double(item)),
+        "backtick" => format!("`{}`", item.replace('`', "``")),
+        _ => item.to_string(),
+    }
+}
+
+/// Splits the buffer into items and joins them back up as a collection literal, like
+/// `('a', 'b')` for an SQL `IN` or `vec!["a", "b"]` for Rust. Items are trimmed and empty ones
+/// are dropped.
+#[derive(Clone, PartialEq)]
+pub struct JoinList {
+    /// An index into `SPLITS`.
+    pub split: usize,
+    /// What to split on when `split` is "delimiter".
+    pub delimiter: String,
+    /// An index into `QUOTES`.
+    pub quote: usize,
+    pub separator: String,
+    /// An index into `WRAPPERS`.
+    pub wrapper: usize,
+    /// Only keep the first of each repeated item.
+    pub dedupe: bool,
+}
+
+impl Default for JoinList {
+    fn default() -> Self {
+        JoinList {
+            split: 0,
+            delimiter: ";".to_string(),
+            quote: 1,
+            separator: ", ".to_string(),
+            wrapper: 1,
+            dedupe: false,
+        }
+    }
+}
+
+impl JoinList {
+    fn items<'a>(&self, input: &'a str) -> Vec<&'a str> {
+        let delimiter = control_escapes(&self.delimiter);
+        let items: Vec<&str> = match SPLITS[self.split] {
+            "commas" => input.split(',').collect(),
+            "tabs" => input.split('\t').collect(),
+            "whitespace" => input.split_whitespace().collect(),
+            "delimiter" if !delimiter.is_empty() => input.split(delimiter.as_str()).collect(),
+            _ => input.lines().collect(),
+        };
+        let mut seen = HashSet::new();
+        items
+            .into_iter()
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+            .filter(|item| !self.dedupe || seen.insert(*item))
+            .collect()
+    }
+}
+
+impl Transform for JoinList {
+    fn name(&self) -> &str {
+        "join list"
+    }
+
+    fn description(&self) -> &str {
+        "join lines into a quoted list, like ('a', 'b')"
+    }
+
+    fn default_key(&self) -> Option<&str> {
+        None
+    }
+
+    fn apply(&self, input: &str) -> anyhow::Result<String> {
+        let joined = self
+            .items(input)
+            .into_iter()
+            .map(|item| quote(item, self.quote))
+            .collect::<Vec<_>>()
+            .join(&control_escapes(&self.separator));
+        Ok(match WRAPPERS[self.wrapper] {
+            "( )" => format!("({})", joined),
+            "[ ]" => format!("[{}]", joined),
+            "{ }" => format!("{{{}}}", joined),
+            "vec![ ]" => format!("vec![{}]", joined),
+            _ => joined,
+        })
+    }
+
+    fn settings(&mut self) -> Vec<Setting<'_>> {
+        vec![
+            Setting::Choice {
+                label: "split on",
+                value: &mut self.split,
+                options: SPLITS,
+            },
+            Setting::Text {
+                label: "delimiter",
+                value: &mut self.delimiter,
+            },
+            Setting::Choice {
+                label: "quotes",
+                value: &mut self.quote,
+                options: QUOTES,
+            },
+            Setting::Text {
+                label: "separator",
+                value: &mut self.separator,
+            },
+            Setting::Choice {
+                label: "wrapper",
+                value: &mut self.wrapper,
+                options: WRAPPERS,
+            },
+            Setting::Toggle {
+                label: "remove duplicates",
+                value: &mut self.dedupe,
+            },
+        ]
+    }
+}
+
+fn closing(open: char) -> Option<char> {
+    match open {
+        '(' => Some(')'),
+        '[' => Some(']'),
+        '{' => Some('}'),
+        _ => None,
+    }
+}
+
+/// Strips the brackets around a collection literal, and anything like `vec!` or `Set.of` in front
+/// of them.
+fn unwrap(input: &str) -> Option<&str> {
+    let start = input.find(['(', '[', '{'])?;
+    let prefix = &input[..start];
+    let wrapped = prefix
+        .chars()
+        .all(|c| c.is_alphanumeric() || "_!.:<> ".contains(c));
+    let open = input[start..].chars().next().unwrap();
+    match closing(open) {
+        Some(close) if wrapped && input.ends_with(close) => {
+            Some(&input[start + 1..input.len() - 1])
+        }
+        _ => None,
+    }
+}
+
+/// Splits on commas that aren't inside quotes or nested brackets, treating quotes the way
+/// `unquote` does. Returns `None` if a bracket
+/// closes that wasn't opened, which means the brackets `unwrap` took off weren't a pair, as in
+/// `(1, 2), (3, 4)`.
+fn split_items(input: &str) -> Option<Vec<&str>> {
+    let mut items = vec![];
+    let mut depth = 0usize;
+    let mut quote = None;
+    let mut escaped = false;
+    let mut start = 0;
+    for (i, c) in input.char_indices() {
+        if let Some(q) = quote {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' && q == '"' {
+                escaped = true;
+            } else if c == q {
+                // A doubled quote closes and reopens, which comes to the same thing.
+                quote = None;
+            }
+            continue;
+        }
+        match c {
+            '\'' | '"' | '`' => quote = Some(c),
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth = depth.checked_sub(1)?,
+            ',' if depth == 0 => {
+                items.push(&input[start..i]);
+                start = i + 1;
+            }
+            _ => (),
+        }
+    }
+    items.push(&input[start..]);
+    Some(items)
+}
+
+/// Removes the quotes around an item, undoing doubled quotes. Backslash escapes are only undone
+/// in double quotes, as in JSON, since SQL strings keep their backslashes.
+fn unquote(item: &str) -> String {
+    let Some(q) = item.chars().next().filter(|c| "'\"`".contains(*c)) else {
+        return item.to_string();
+    };
+    if item.len() < 2 || !item.ends_with(q) {
+        return item.to_string();
+    }
+    let mut out = String::new();
+    let mut chars = item[1..item.len() - 1].chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if q == '"' => match chars.next() {
+                Some('n') => out.push('\n'),
+                Some('t') => out.push('\t'),
+                Some('r') => out.push('\r'),
+                Some('u') => {
+                    let hex: String = chars.clone().take(4).collect();
+                    match u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32) {
+                        Some(c) if hex.len() == 4 => {
+                            out.push(c);
+                            chars.nth(3);
+                        }
+                        _ => out.push('u'),
+                    }
+                }
+                Some(c) => out.push(c),
+                None => out.push('\\'),
+            },
+            c if c == q && chars.peek() == Some(&q) => {
+                chars.next();
+                out.push(q);
+            }
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// The reverse of `JoinList`: splits a collection literal like `["a", "b"]` or `('a', 'b')` back
+/// into one item per line.
+pub struct SplitList;
+
+impl Transform for SplitList {
+    fn name(&self) -> &str {
+        "split list"
+    }
+
+    fn description(&self) -> &str {
+        "split a list literal into lines, removing quotes"
+    }
+
+    fn default_key(&self) -> Option<&str> {
+        None
+    }
+
+    fn apply(&self, input: &str) -> anyhow::Result<String> {
+        let input = input.trim().trim_end_matches(';');
+        let items = unwrap(input)
+            .and_then(split_items)
+            .or_else(|| split_items(input))
+            .unwrap_or_else(|| vec![input]);
+        Ok(items
+            .into_iter()
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+            .map(unquote)
+            .collect::<Vec<_>>()
+            .join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn joins_sql_lists() {
+        assert_eq!(
+            JoinList::default().apply("a\n O'Neil \n\nc\na").unwrap(),
+            "('a', 'O''Neil', 'c', 'a')"
+        );
+        let dedupe = JoinList {
+            dedupe: true,
+            ..JoinList::default()
+        };
+        assert_eq!(dedupe.apply("a\nb\na").unwrap(), "('a', 'b')");
+    }
+
+    #[test]
+    fn splits_and_wraps() {
+        let list = JoinList {
+            split: 4,
+            delimiter: "\\t".to_string(),
+            quote: 0,
+            separator: "\\n".to_string(),
+            wrapper: 4,
+            dedupe: false,
+        };
+        assert_eq!(list.apply("1\t2\t3").unwrap(), "vec![1\n2\n3]");
+        let whitespace = JoinList {
+            split: 3,
+            quote: 3,
+            wrapper: 0,
+            ..JoinList::default()
+        };
+        assert_eq!(whitespace.apply("a  b`c").unwrap(), "`a`, `b``c`");
+    }
+
+    #[test]
+    fn double_quotes_escape_control_characters() {
+        let list = JoinList {
+            split: 4,
+            quote: 2,
+            wrapper: 2,
+            ..JoinList::default()
+        };
+        let joined = list.apply("say \"hi\"\\;a\tb\nc\r\u{1}d").unwrap();
+        assert_eq!(joined, r#"["say \"hi\"\\", "a\tb\nc\r\u0001d"]"#);
+        let parsed: Vec<String> = serde_json::from_str(&joined).unwrap();
+        assert_eq!(parsed, ["say \"hi\"\\", "a\tb\nc\r\u{1}d"]);
+        assert_eq!(
+            SplitList.apply(&joined).unwrap(),
+            "say \"hi\"\\\na\tb\nc\r\u{1}d"
+        );
+    }
+
+    #[test]
+    fn splits_literals() {
+        assert_eq!(
+            SplitList.apply("('a', 'b,c', 'd''e');").unwrap(),
+            "a\nb,c\nd'e"
+        );
+        assert_eq!(
+            SplitList.apply("vec![1, [2, 3], 4]").unwrap(),
+            "1\n[2, 3]\n4"
+        );
+        assert_eq!(SplitList.apply("Set.of(\"x\")").unwrap(), "x");
+        assert_eq!(SplitList.apply("(1, 2), (3, 4)").unwrap(), "(1, 2)\n(3, 4)");
+        assert_eq!(SplitList.apply("a, b").unwrap(), "a\nb");
+        assert_eq!(SplitList.apply("\"\\u00zz\"").unwrap(), "u00zz");
+    }
+
+    #[test]
+    fn split_list_round_trips() {
+        let items = "x\ny'z\n[1]";
+        for quote in 1..QUOTES.len() {
+            let list = JoinList {
+                quote,
+                ..JoinList::default()
+            };
+            assert_eq!(SplitList.apply(&list.apply(items).unwrap()).unwrap(), items);
+        }
+    }
+}
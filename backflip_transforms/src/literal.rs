@@ -0,0 +1,762 @@
+use std::iter::Peekable;
+use std::str::Chars;
+
+use crate::{Setting, Transform};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Language {
+    Rust,
+    C,
+    Python,
+    JavaScript,
+    Shell,
+    PowerShell,
+    Cmd,
+    Sql,
+    Csv,
+}
+
+const LANGUAGES: &[Language] = &[
+    Language::Rust,
+    Language::C,
+    Language::Python,
+    Language::JavaScript,
+    Language::Shell,
+    Language::PowerShell,
+    Language::Cmd,
+    Language::Sql,
+    Language::Csv,
+];
+
+const LANGUAGE_NAMES: &[&str] = &[
+    "rust",
+    "c/c++",
+    "python",
+    "javascript template",
+    "posix shell",
+    "powershell",
+    "cmd.exe",
+    "sql",
+    "csv field",
+];
+
+impl Language {
+    pub fn escape(self, s: &str) -> anyhow::Result<String> {
+        Ok(match self {
+            Language::Rust => escape_rust(s),
+            Language::C => escape_c(s),
+            Language::Python => escape_python(s),
+            Language::JavaScript => escape_template(s),
+            Language::Shell => escape_shell(s),
+            // PowerShell treats the curly single quotes as quotes too.
+            Language::PowerShell => double_quotes(s, '\'', &['\'', '‘', '’', '‚', '‛']),
+            Language::Cmd => escape_cmd(s)?,
+            Language::Sql => double_quotes(s, '\'', &['\'']),
+            Language::Csv => escape_csv(s),
+        })
+    }
+
+    /// The reverse of `escape`, which also understands the other ways the language has of
+    /// writing strings, like Python's byte strings or shell double quotes.
+    pub fn unescape(self, s: &str) -> anyhow::Result<String> {
+        let s = s.trim();
+        match self {
+            Language::Rust => unescape_rust(s),
+            Language::C => unescape_c(s),
+            Language::Python => unescape_python(s),
+            Language::JavaScript => unescape_template(s),
+            Language::Shell => unescape_shell(s),
+            Language::PowerShell => unescape_powershell(s),
+            Language::Cmd => Ok(unescape_cmd(s)),
+            Language::Sql => {
+                let s = s.strip_prefix(['N', 'n']).unwrap_or(s);
+                Ok(unquote(s, "'", "'")?.replace("''", "'"))
+            }
+            Language::Csv => Ok(match s.strip_prefix('"') {
+                Some(_) => unquote(s, "\"", "\"")?.replace("\"\"", "\""),
+                None => s.to_string(),
+            }),
+        }
+    }
+}
+
+/// Wraps `s` in `quote`, doubling any of `doubled` inside it.
+fn double_quotes(s: &str, quote: char, doubled: &[char]) -> String {
+    let mut out = String::from(quote);
+    for c in s.chars() {
+        if doubled.contains(&c) {
+            out.push(c);
+        }
+        out.push(c);
+    }
+    out.push(quote);
+    out
+}
+
+fn unquote<'a>(s: &'a str, open: &str, close: &str) -> anyhow::Result<&'a str> {
+    s.strip_prefix(open)
+        .and_then(|s| s.strip_suffix(close))
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "expected a string starting with {} and ending with {}",
+                open,
+                close
+            )
+        })
+}
+
+fn take_hex(chars: &mut Peekable<Chars>, min: usize, max: usize) -> anyhow::Result<u32> {
+    let mut digits = String::new();
+    while digits.len() < max {
+        match chars.peek() {
+            Some(c) if c.is_ascii_hexdigit() => digits.push(chars.next().unwrap()),
+            _ => break,
+        }
+    }
+    if digits.len() < min {
+        anyhow::bail!("expected at least {} hex digits in an escape", min);
+    }
+    Ok(u32::from_str_radix(&digits, 16)?)
+}
+
+/// Up to three octal digits, `first` being the one after the backslash.
+fn take_octal(first: char, chars: &mut Peekable<Chars>) -> u32 {
+    let mut value = first.to_digit(8).unwrap_or_default();
+    for _ in 0..2 {
+        match chars.next_if(|c| c.is_digit(8)) {
+            Some(c) => value = value * 8 + c.to_digit(8).unwrap_or_default(),
+            None => break,
+        }
+    }
+    value
+}
+
+fn octal_byte(value: u32) -> anyhow::Result<u8> {
+    u8::try_from(value).map_err(|_| anyhow::anyhow!("\\{:o} is more than a byte", value))
+}
+
+/// `\u{1F600}`, as in Rust and JavaScript.
+fn take_braced_hex(chars: &mut Peekable<Chars>) -> anyhow::Result<u32> {
+    if chars.next() != Some('{') {
+        anyhow::bail!("expected {{ after \\u");
+    }
+    let value = take_hex(chars, 1, 6)?;
+    if chars.next() != Some('}') {
+        anyhow::bail!("expected }} to end a \\u{{...}} escape");
+    }
+    Ok(value)
+}
+
+/// The output of unescaping. Escapes like C's `\xff` make single bytes, which only have to come
+/// out as UTF-8 once they're all put together.
+#[derive(Default)]
+struct Unescaped(Vec<u8>);
+
+impl Unescaped {
+    fn char(&mut self, c: char) {
+        self.0
+            .extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes());
+    }
+
+    fn code_point(&mut self, value: u32) -> anyhow::Result<()> {
+        let c = char::from_u32(value)
+            .ok_or_else(|| anyhow::anyhow!("{:#x} isn't a unicode scalar value", value))?;
+        self.char(c);
+        Ok(())
+    }
+
+    fn byte(&mut self, byte: u8) {
+        self.0.push(byte);
+    }
+
+    fn finish(self) -> anyhow::Result<String> {
+        String::from_utf8(self.0)
+            .map_err(|e| anyhow::anyhow!("the escaped bytes aren't utf-8: {}", e))
+    }
+}
+
+/// Drops the whitespace after a backslash-newline, which is how Rust continues a string on the
+/// next line.
+fn skip_whitespace(chars: &mut Peekable<Chars>) {
+    while chars.next_if(|c| c.is_whitespace()).is_some() {}
+}
+
+fn escape_rust(s: &str) -> String {
+    // A raw string is easier to read when there are quotes or backslashes to escape, as long as
+    // there's nothing in it that can only be written as an escape.
+    let plain = s.chars().all(|c| !c.is_control() || c == '\n' || c == '\t');
+    if plain && s.contains(['"', '\\']) {
+        let hashes = s
+            .match_indices('"')
+            .map(|(i, _)| {
+                let after = &s[i + 1..];
+                after.len() - after.trim_start_matches('#').len() + 1
+            })
+            .max()
+            .unwrap_or(0);
+        let hashes = "#".repeat(hashes);
+        return format!("r{}\"{}\"{}", hashes, s, hashes);
+    }
+    let mut out = String::from('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            '\0' => out.push_str("\\0"),
+            c if c.is_control() => out.push_str(&format!("\\u{{{:x}}}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn unescape_rust(s: &str) -> anyhow::Result<String> {
+    if let Some(raw) = s.strip_prefix('r') {
+        let hashes = "#".repeat(raw.len() - raw.trim_start_matches('#').len());
+        return Ok(unquote(raw, &format!("{}\"", hashes), &format!("\"{}", hashes))?.to_string());
+    }
+    let mut chars = unquote(s, "\"", "\"")?.chars().peekable();
+    let mut out = Unescaped::default();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.char(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.char('\n'),
+            Some('r') => out.char('\r'),
+            Some('t') => out.char('\t'),
+            Some('0') => out.char('\0'),
+            Some(c @ ('\\' | '\'' | '"')) => out.char(c),
+            Some('x') => match take_hex(&mut chars, 2, 2)? {
+                value @ 0..=0x7f => out.code_point(value)?,
+                value => anyhow::bail!("\\x{:x} is out of range, use \\u{{{:x}}}", value, value),
+            },
+            Some('u') => out.code_point(take_braced_hex(&mut chars)?)?,
+            Some('\n') => skip_whitespace(&mut chars),
+            Some('\r') if chars.next_if_eq(&'\n').is_some() => skip_whitespace(&mut chars),
+            Some(c) => anyhow::bail!("\\{} isn't an escape in rust", c),
+            None => anyhow::bail!("the string ends with a lone backslash"),
+        }
+    }
+    out.finish()
+}
+
+fn escape_c(s: &str) -> String {
+    let mut out = String::from('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            '\x07' => out.push_str("\\a"),
+            '\x08' => out.push_str("\\b"),
+            '\x0b' => out.push_str("\\v"),
+            '\x0c' => out.push_str("\\f"),
+            // Octal, because a hex escape would swallow any hex digits after it.
+            c if c.is_ascii_control() => out.push_str(&format!("\\{:03o}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn unescape_c(s: &str) -> anyhow::Result<String> {
+    let s = ["u8", "u", "U", "L"]
+        .iter()
+        .find_map(|prefix| s.strip_prefix(prefix).filter(|s| s.starts_with('"')))
+        .unwrap_or(s);
+    let mut chars = unquote(s, "\"", "\"")?.chars().peekable();
+    let mut out = Unescaped::default();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.char(c);
+            continue;
+        }
+        match chars.next() {
+            Some('a') => out.char('\x07'),
+            Some('b') => out.char('\x08'),
+            Some('f') => out.char('\x0c'),
+            Some('n') => out.char('\n'),
+            Some('r') => out.char('\r'),
+            Some('t') => out.char('\t'),
+            Some('v') => out.char('\x0b'),
+            Some(c @ ('\\' | '\'' | '"' | '?')) => out.char(c),
+            Some(c @ '0'..='7') => out.byte(octal_byte(take_octal(c, &mut chars))?),
+            Some('x') => {
+                let value = take_hex(&mut chars, 1, 8)?;
+                out.byte(
+                    u8::try_from(value)
+                        .map_err(|_| anyhow::anyhow!("\\x{:x} is more than a byte", value))?,
+                );
+            }
+            Some('u') => out.code_point(take_hex(&mut chars, 4, 4)?)?,
+            Some('U') => out.code_point(take_hex(&mut chars, 8, 8)?)?,
+            Some('\n') => (),
+            Some(c) => anyhow::bail!("\\{} isn't an escape in c", c),
+            None => anyhow::bail!("the string ends with a lone backslash"),
+        }
+    }
+    out.finish()
+}
+
+/// Quotes the way Python's `repr` does: single quotes unless the text has single quotes and no
+/// double quotes in it.
+fn escape_python(s: &str) -> String {
+    let quote = if s.contains('\'') && !s.contains('"') {
+        '"'
+    } else {
+        '\''
+    };
+    let mut out = String::from(quote);
+    for c in s.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c == quote => {
+                out.push('\\');
+                out.push(c);
+            }
+            c if (c as u32) < 0x100 && c.is_control() => {
+                out.push_str(&format!("\\x{:02x}", c as u32))
+            }
+            c if c.is_control() => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push(quote);
+    out
+}
+
+fn unescape_python(s: &str) -> anyhow::Result<String> {
+    let prefix_len = s.len() - s.trim_start_matches(char::is_alphabetic).len();
+    let prefix = s[..prefix_len].to_ascii_lowercase();
+    if prefix.chars().any(|c| !"rbu".contains(c)) {
+        anyhow::bail!(
+            "can't unescape a string with the prefix {}",
+            &s[..prefix_len]
+        );
+    }
+    let s = &s[prefix_len..];
+    let quote = ["\"\"\"", "'''", "\"", "'"]
+        .into_iter()
+        .find(|quote| s.starts_with(quote))
+        .unwrap_or("'");
+    let body = unquote(s, quote, quote)?;
+    if prefix.contains('r') {
+        return Ok(body.to_string());
+    }
+    // `\xe9` is a byte in a bytes literal, but the character U+00E9 in a str.
+    let bytes = prefix.contains('b');
+    let mut chars = body.chars().peekable();
+    let mut out = Unescaped::default();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.char(c);
+            continue;
+        }
+        match chars.next() {
+            Some('a') => out.char('\x07'),
+            Some('b') => out.char('\x08'),
+            Some('f') => out.char('\x0c'),
+            Some('n') => out.char('\n'),
+            Some('r') => out.char('\r'),
+            Some('t') => out.char('\t'),
+            Some('v') => out.char('\x0b'),
+            Some(c @ ('\\' | '\'' | '"')) => out.char(c),
+            Some('\n') => (),
+            Some(c @ '0'..='7') if bytes => out.byte(octal_byte(take_octal(c, &mut chars))?),
+            // In a str, `\777` is the character U+01FF.
+            Some(c @ '0'..='7') => out.code_point(take_octal(c, &mut chars))?,
+            Some('x') if bytes => out.byte(take_hex(&mut chars, 2, 2)? as u8),
+            Some('x') => out.code_point(take_hex(&mut chars, 2, 2)?)?,
+            Some('u') if !bytes => out.code_point(take_hex(&mut chars, 4, 4)?)?,
+            Some('U') if !bytes => out.code_point(take_hex(&mut chars, 8, 8)?)?,
+            Some('N') if !bytes => anyhow::bail!("\\N{{...}} escapes aren't supported"),
+            // Python keeps the backslash of anything that isn't an escape.
+            Some(c) => {
+                out.char('\\');
+                out.char(c);
+            }
+            None => anyhow::bail!("the string ends with a lone backslash"),
+        }
+    }
+    out.finish()
+}
+
+fn escape_template(s: &str) -> String {
+    let mut out = String::from('`');
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '`' => out.push_str("\\`"),
+            '\\' => out.push_str("\\\\"),
+            '$' if chars.peek() == Some(&'{') => out.push_str("\\$"),
+            // Template literals turn line breaks into plain \n.
+            '\r' => out.push_str("\\r"),
+            c => out.push(c),
+        }
+    }
+    out.push('`');
+    out
+}
+
+fn unescape_template(s: &str) -> anyhow::Result<String> {
+    let mut chars = unquote(s, "`", "`")?.chars().peekable();
+    let mut out = Unescaped::default();
+    while let Some(c) = chars.next() {
+        match c {
+            '$' if chars.peek() == Some(&'{') => {
+                anyhow::bail!("the template has a ${{...}} substitution in it")
+            }
+            '\r' => {
+                chars.next_if_eq(&'\n');
+                out.char('\n');
+            }
+            '\\' => match chars.next() {
+                Some('b') => out.char('\x08'),
+                Some('f') => out.char('\x0c'),
+                Some('n') => out.char('\n'),
+                Some('r') => out.char('\r'),
+                Some('t') => out.char('\t'),
+                Some('v') => out.char('\x0b'),
+                Some('0') if !chars.peek().is_some_and(char::is_ascii_digit) => out.char('\0'),
+                Some('0') => anyhow::bail!("\\0 followed by a digit isn't allowed in a template"),
+                Some('x') => out.code_point(take_hex(&mut chars, 2, 2)?)?,
+                Some('u') if chars.peek() == Some(&'{') => {
+                    out.code_point(take_braced_hex(&mut chars)?)?
+                }
+                Some('u') => {
+                    let high = take_hex(&mut chars, 4, 4)?;
+                    // Characters outside the BMP are written as a surrogate pair.
+                    if (0xd800..0xdc00).contains(&high) {
+                        let mut rest = chars.clone();
+                        if rest.next() == Some('\\') && rest.next() == Some('u') {
+                            let low = take_hex(&mut rest, 4, 4)?;
+                            if (0xdc00..0xe000).contains(&low) {
+                                chars = rest;
+                                out.code_point(0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00))?;
+                                continue;
+                            }
+                        }
+                    }
+                    out.code_point(high)?
+                }
+                Some('\r') => {
+                    chars.next_if_eq(&'\n');
+                }
+                Some('\n' | '\u{2028}' | '\u{2029}') => (),
+                Some(c @ '1'..='9') => anyhow::bail!("\\{} isn't allowed in a template", c),
+                Some(c) => out.char(c),
+                None => anyhow::bail!("the string ends with a lone backslash"),
+            },
+            c => out.char(c),
+        }
+    }
+    out.finish()
+}
+
+fn escape_shell(s: &str) -> String {
+    let safe = |c: char| c.is_ascii_alphanumeric() || "_@%+=:,./-".contains(c);
+    if !s.is_empty() && s.chars().all(safe) {
+        return s.to_string();
+    }
+    format!("'{}'", s.replace('\'', r"'\''"))
+}
+
+/// Reads a shell word, joining together its quoted and unquoted parts.
+fn unescape_shell(s: &str) -> anyhow::Result<String> {
+    let mut chars = s.chars().peekable();
+    let mut out = String::new();
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => loop {
+                match chars.next() {
+                    Some('\'') => break,
+                    Some(c) => out.push(c),
+                    None => anyhow::bail!("a single quote isn't closed"),
+                }
+            },
+            '"' => loop {
+                match chars.next() {
+                    Some('"') => break,
+                    Some('\\') => match chars.next() {
+                        Some('\n') => (),
+                        Some(c @ ('$' | '`' | '"' | '\\')) => out.push(c),
+                        Some(c) => {
+                            out.push('\\');
+                            out.push(c);
+                        }
+                        None => anyhow::bail!("a double quote isn't closed"),
+                    },
+                    Some(c @ ('$' | '`')) => {
+                        anyhow::bail!("the string has {} expansions in it", c)
+                    }
+                    Some(c) => out.push(c),
+                    None => anyhow::bail!("a double quote isn't closed"),
+                }
+            },
+            '\\' => match chars.next() {
+                Some('\n') | None => (),
+                Some(c) => out.push(c),
+            },
+            '$' | '`' => anyhow::bail!("the string has {} expansions in it", c),
+            c => out.push(c),
+        }
+    }
+    Ok(out)
+}
+
+fn unescape_powershell(s: &str) -> anyhow::Result<String> {
+    if s.starts_with(['\'', '‘', '’', '‚', '‛']) {
+        let body = s
+            .strip_prefix(|c| "'‘’‚‛".contains(c))
+            .and_then(|s| s.strip_suffix(|c| "'‘’‚‛".contains(c)))
+            .ok_or_else(|| anyhow::anyhow!("a single quote isn't closed"))?;
+        let mut out = String::new();
+        let mut chars = body.chars().peekable();
+        while let Some(c) = chars.next() {
+            out.push(c);
+            if "'‘’‚‛".contains(c) {
+                chars.next_if(|next| "'‘’‚‛".contains(*next));
+            }
+        }
+        return Ok(out);
+    }
+    let mut chars = unquote(s, "\"", "\"")?.chars().peekable();
+    let mut out = Unescaped::default();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                chars.next_if_eq(&'"');
+                out.char('"');
+            }
+            '$' if chars
+                .peek()
+                .is_some_and(|c| c.is_alphanumeric() || "_({:".contains(*c)) =>
+            {
+                anyhow::bail!("the string has variables or subexpressions in it")
+            }
+            '`' => match chars.next() {
+                Some('0') => out.char('\0'),
+                Some('a') => out.char('\x07'),
+                Some('b') => out.char('\x08'),
+                Some('e') => out.char('\x1b'),
+                Some('f') => out.char('\x0c'),
+                Some('n') => out.char('\n'),
+                Some('r') => out.char('\r'),
+                Some('t') => out.char('\t'),
+                Some('v') => out.char('\x0b'),
+                Some('u') => out.code_point(take_braced_hex(&mut chars)?)?,
+                Some(c) => out.char(c),
+                None => anyhow::bail!("the string ends with a lone backtick"),
+            },
+            c => out.char(c),
+        }
+    }
+    out.finish()
+}
+
+/// Quotes an argument for a batch file. Inside the quotes `""` stands for a quote, which is
+/// what most programs' argument parsers expect, and `%%` keeps a percent sign from starting a
+/// variable.
+fn escape_cmd(s: &str) -> anyhow::Result<String> {
+    if s.contains(['\n', '\r']) {
+        anyhow::bail!("cmd.exe can't take line breaks in an argument");
+    }
+    Ok(format!("\"{}\"", s.replace('"', "\"\"").replace('%', "%%")))
+}
+
+fn unescape_cmd(s: &str) -> String {
+    let mut chars = s.chars().peekable();
+    let mut out = String::new();
+    let mut quoted = false;
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.next_if_eq(&'"').is_some() => out.push('"'),
+            '"' => quoted = !quoted,
+            '%' if chars.next_if_eq(&'%').is_some() => out.push('%'),
+            '^' if !quoted => out.extend(chars.next()),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+fn escape_csv(s: &str) -> String {
+    let needs_quotes = s.contains([',', '"', '\n', '\r']) || s.starts_with(' ') || s.ends_with(' ');
+    if needs_quotes {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+/// Wraps the buffer in a string literal for the chosen language.
+#[derive(Default)]
+pub struct EscapeString {
+    /// An index into `LANGUAGES`.
+    pub language: usize,
+}
+
+impl Transform for EscapeString {
+    fn name(&self) -> &str {
+        "escape string"
+    }
+
+    fn description(&self) -> &str {
+        "wrap the text in a string literal for a language or shell"
+    }
+
+    fn default_key(&self) -> Option<&str> {
+        None
+    }
+
+    fn apply(&self, input: &str) -> anyhow::Result<String> {
+        LANGUAGES[self.language].escape(input)
+    }
+
+    fn settings(&mut self) -> Vec<Setting<'_>> {
+        vec![Setting::Choice {
+            label: "language",
+            value: &mut self.language,
+            options: LANGUAGE_NAMES,
+        }]
+    }
+}
+
+/// Unwraps a string literal written in the chosen language.
+#[derive(Default)]
+pub struct UnescapeString {
+    /// An index into `LANGUAGES`.
+    pub language: usize,
+}
+
+impl Transform for UnescapeString {
+    fn name(&self) -> &str {
+        "unescape string"
+    }
+
+    fn description(&self) -> &str {
+        "unwrap a string literal from a language or shell"
+    }
+
+    fn default_key(&self) -> Option<&str> {
+        None
+    }
+
+    fn apply(&self, input: &str) -> anyhow::Result<String> {
+        LANGUAGES[self.language].unescape(input)
+    }
+
+    fn settings(&mut self) -> Vec<Setting<'_>> {
+        vec![Setting::Choice {
+            label: "language",
+            value: &mut self.language,
+            options: LANGUAGE_NAMES,
+        }]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_language_round_trips() {
+        let text = "it's \"quoted\" \\ $HOME ${x} `tick` 100% ^caret\ttab\u{1}é😀";
+        for (language, name) in LANGUAGES.iter().zip(LANGUAGE_NAMES) {
+            let escaped = language.escape(text).unwrap();
+            assert_eq!(language.unescape(&escaped).unwrap(), text, "{}", name);
+            if *language != Language::Cmd {
+                let lines = "a\r\nb\n";
+                let escaped = language.escape(lines).unwrap();
+                assert_eq!(language.unescape(&escaped).unwrap(), lines, "{}", name);
+            }
+        }
+        assert!(Language::Cmd.escape("a\nb").is_err());
+    }
+
+    #[test]
+    fn escapes_readably() {
+        assert_eq!(
+            Language::Rust.escape(r#"say "hi""#).unwrap(),
+            r##"r#"say "hi""#"##
+        );
+        assert_eq!(Language::Rust.escape("a\0").unwrap(), r#""a\0""#);
+        assert_eq!(Language::C.escape("\x01").unwrap(), r#""\001""#);
+        assert_eq!(Language::Python.escape("it's").unwrap(), r#""it's""#);
+        assert_eq!(Language::Shell.escape("a.txt").unwrap(), "a.txt");
+        assert_eq!(Language::Shell.escape("it's").unwrap(), r"'it'\''s'");
+        assert_eq!(Language::Sql.escape("it's").unwrap(), "'it''s'");
+        assert_eq!(Language::Csv.escape("a,b").unwrap(), "\"a,b\"");
+    }
+
+    #[test]
+    fn c_escapes() {
+        let unescape = |s| Language::C.unescape(s);
+        assert_eq!(unescape(r#"u8"\x41\101é\?""#).unwrap(), "AAé?");
+        assert_eq!(unescape(r#""\xc3\xa9""#).unwrap(), "é");
+        assert!(unescape(r#""\xff""#).is_err());
+        assert!(unescape(r#""\400""#).is_err());
+        assert!(unescape(r#""\q""#).is_err());
+    }
+
+    #[test]
+    fn python_escapes() {
+        let unescape = |s| Language::Python.unescape(s);
+        assert_eq!(unescape(r"'\777\x41\q'").unwrap(), "\u{1ff}A\\q");
+        assert!(unescape(r"'\N{DASH}'").is_err());
+        assert_eq!(unescape(r"'\1234'").unwrap(), "S4");
+        assert_eq!(unescape(r"b'\303\251'").unwrap(), "é");
+        assert!(unescape(r"b'\777'").is_err());
+        assert_eq!(unescape(r"r'\n'").unwrap(), r"\n");
+        assert_eq!(unescape("'''a\nb'''").unwrap(), "a\nb");
+        assert!(unescape("f'{x}'").is_err());
+    }
+
+    #[test]
+    fn template_escapes() {
+        let unescape = |s| Language::JavaScript.unescape(s);
+        assert_eq!(unescape(r"`\0😀\u{e9}`").unwrap(), "\0😀é");
+        assert!(unescape(r"`\01`").is_err());
+        assert!(unescape(r"`\1`").is_err());
+        assert!(unescape("`${x}`").is_err());
+        assert_eq!(unescape("`a\r\nb`").unwrap(), "a\nb");
+    }
+
+    #[test]
+    fn shell_words() {
+        let unescape = |s| Language::Shell.unescape(s);
+        assert_eq!(unescape(r#"'a b'"c \" d"e\ f"#).unwrap(), r#"a bc " de f"#);
+        assert!(unescape("\"$HOME\"").is_err());
+        assert!(unescape("'open").is_err());
+        assert_eq!(
+            Language::Cmd.unescape(r#"a^&b "c ""d"" 100%%""#).unwrap(),
+            r#"a&b c "d" 100%"#
+        );
+        assert_eq!(
+            Language::PowerShell.unescape("\"`t`u{e9}\"").unwrap(),
+            "\té"
+        );
+        assert!(Language::PowerShell.unescape("\"$env:PATH\"").is_err());
+    }
+
+    #[test]
+    fn rust_escapes() {
+        let unescape = |s| Language::Rust.unescape(s);
+        assert_eq!(unescape("\"a\\\n    b\\u{1F600}\\x41\"").unwrap(), "ab😀A");
+        assert!(unescape(r#""\x80""#).is_err());
+        assert!(unescape(r#""\u{d800}""#).is_err());
+        assert_eq!(unescape(r###"r##"a"#b"##"###).unwrap(), "a\"#b");
+    }
+}
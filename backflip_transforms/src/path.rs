@@ -0,0 +1,457 @@
+use percent_encoding::{percent_decode_str, utf8_percent_encode};
+
+use crate::{Setting, Transform};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Flavor {
+    /// `C:\Users\me`, `\\server\share`
+    Windows,
+    /// `C:/Users/me`, `//server/share`, as git and cmake like them.
+    Mixed,
+    /// `/mnt/c/Users/me`
+    Wsl,
+    /// `/c/Users/me`, as in git bash and MSYS2.
+    Msys,
+    /// `file:///C:/Users/me`, `file://server/share`
+    FileUri,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Root {
+    /// A relative path.
+    None,
+    /// `/`, when it isn't a mount of a Windows drive.
+    Posix,
+    Drive(char),
+    Unc {
+        server: String,
+        share: String,
+    },
+}
+
+/// A path broken into the parts that mean the same thing in every flavor.
+#[derive(Debug, PartialEq, Eq)]
+pub struct ParsedPath {
+    root: Root,
+    components: Vec<String>,
+    trailing_slash: bool,
+}
+
+/// `c` in `c`, `c/...`, used for the drive letters in WSL and MSYS paths.
+fn drive_letter(s: &str) -> Option<(char, &str)> {
+    let mut chars = s.chars();
+    let letter = chars.next().filter(char::is_ascii_alphabetic)?;
+    let rest = chars.as_str();
+    (rest.is_empty() || rest.starts_with('/')).then_some((letter, rest))
+}
+
+fn split(path: &str, root: Root) -> ParsedPath {
+    ParsedPath {
+        root,
+        components: path
+            .split('/')
+            .filter(|c| !c.is_empty())
+            .map(str::to_string)
+            .collect(),
+        trailing_slash: path.len() > 1 && path.ends_with('/'),
+    }
+}
+
+fn parse_uri(rest: &str) -> anyhow::Result<ParsedPath> {
+    let Some(rest) = rest.strip_prefix("//") else {
+        anyhow::bail!("expected file:// at the start of the uri");
+    };
+    let (host, path) = rest.split_at(rest.find('/').unwrap_or(rest.len()));
+    let decoded: String = path
+        .split('/')
+        .map(|c| {
+            percent_decode_str(c)
+                .decode_utf8_lossy()
+                .replace('/', "%2F")
+        })
+        .collect::<Vec<_>>()
+        .join("/");
+    if !host.is_empty() && !host.eq_ignore_ascii_case("localhost") {
+        return parse(&format!("//{}{}", host, decoded), false);
+    }
+    // `file:///C:/x` has an extra slash before the drive.
+    match decoded.strip_prefix('/') {
+        Some(drive) if drive.get(1..2) == Some(":") => parse(drive, false),
+        _ => Ok(split(&decoded, Root::Posix)),
+    }
+}
+
+/// Works out what flavor `input` is in and breaks it into parts. Forward slashes and
+/// backslashes are both accepted as separators, except in paths that start with a `/` and
+/// aren't on a drive, where a backslash can be part of a file name. `/c/...` is only read as
+/// drive C, the way MSYS writes it, when `msys` is set, since otherwise it's an ordinary unix
+/// path.
+pub fn parse(input: &str, msys: bool) -> anyhow::Result<ParsedPath> {
+    if input
+        .get(..5)
+        .is_some_and(|scheme| scheme.eq_ignore_ascii_case("file:"))
+    {
+        return parse_uri(&input[5..]);
+    }
+    // `\\?\C:\x` and `\\?\UNC\server\share` turn off Windows' path parsing, which doesn't
+    // change what they point to.
+    let input = match input.strip_prefix(r"\\?\") {
+        Some(rest)
+            if rest
+                .get(..4)
+                .is_some_and(|unc| unc.eq_ignore_ascii_case(r"UNC\")) =>
+        {
+            format!(r"\\{}", &rest[4..])
+        }
+        Some(rest) => rest.to_string(),
+        None => input.to_string(),
+    };
+
+    let bytes = input.as_bytes();
+    let windows = (input.contains('\\') && !input.starts_with('/'))
+        || (bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':');
+    if windows || input.starts_with("//") {
+        let path = input.replace('\\', "/");
+        if let Some(rest) = path.strip_prefix("//") {
+            let mut parts = rest.splitn(3, '/');
+            let server = parts.next().unwrap_or_default().to_string();
+            let share = parts.next().unwrap_or_default().to_string();
+            if server.is_empty() || share.is_empty() {
+                anyhow::bail!("a unc path needs a server and a share, like \\\\server\\share");
+            }
+            let root = Root::Unc { server, share };
+            return Ok(split(
+                &format!("/{}", parts.next().unwrap_or_default()),
+                root,
+            ));
+        }
+        if path.as_bytes().get(1) == Some(&b':') {
+            let letter = path.chars().next().unwrap();
+            return Ok(split(&path[2..], Root::Drive(letter.to_ascii_uppercase())));
+        }
+        return Ok(split(&path, Root::None));
+    }
+
+    if let Some((letter, rest)) = input.strip_prefix("/mnt/").and_then(drive_letter) {
+        return Ok(split(rest, Root::Drive(letter.to_ascii_uppercase())));
+    }
+    if let Some((letter, rest)) = input
+        .strip_prefix('/')
+        .filter(|_| msys)
+        .and_then(drive_letter)
+    {
+        return Ok(split(rest, Root::Drive(letter.to_ascii_uppercase())));
+    }
+    if input.starts_with('/') {
+        return Ok(split(&input, Root::Posix));
+    }
+    Ok(split(&input, Root::None))
+}
+
+impl ParsedPath {
+    pub fn write(&self, flavor: Flavor) -> anyhow::Result<String> {
+        let separator = if flavor == Flavor::Windows { "\\" } else { "/" };
+        let components: Vec<String> = if flavor == Flavor::FileUri {
+            self.components
+                .iter()
+                .map(|c| utf8_percent_encode(c, crate::url::PATH).to_string())
+                .collect()
+        } else {
+            self.components.clone()
+        };
+        let root = match (&self.root, flavor) {
+            (Root::None, Flavor::FileUri) => anyhow::bail!("a file uri needs an absolute path"),
+            (Root::None, _) => String::new(),
+            (Root::Posix, Flavor::Windows | Flavor::Mixed) => {
+                anyhow::bail!("this path isn't on a windows drive")
+            }
+            (Root::Posix, Flavor::FileUri) => "file:///".to_string(),
+            (Root::Posix, _) => "/".to_string(),
+            (Root::Drive(letter), Flavor::Windows | Flavor::Mixed) => {
+                format!("{}:{}", letter, separator)
+            }
+            (Root::Drive(letter), Flavor::Wsl) => {
+                format!("/mnt/{}/", letter.to_ascii_lowercase())
+            }
+            (Root::Drive(letter), Flavor::Msys) => format!("/{}/", letter.to_ascii_lowercase()),
+            (Root::Drive(letter), Flavor::FileUri) => format!("file:///{}:/", letter),
+            (Root::Unc { .. }, Flavor::Wsl) => {
+                anyhow::bail!("wsl can't open unc paths without mounting the share first")
+            }
+            (Root::Unc { server, share }, Flavor::FileUri) => {
+                format!("file://{}/{}/", server, share)
+            }
+            (Root::Unc { server, share }, _) => {
+                format!("{0}{0}{1}{0}{2}{0}", separator, server, share)
+            }
+        };
+
+        let mut path = root + &components.join(separator);
+        if self.components.is_empty() {
+            // Only the drive roots in Windows' own syntax keep their trailing separator.
+            let keep = matches!(
+                (&self.root, flavor),
+                (
+                    Root::Drive(_),
+                    Flavor::Windows | Flavor::Mixed | Flavor::FileUri
+                ) | (Root::Posix, _)
+            );
+            if !keep {
+                path.pop();
+            }
+        } else if self.trailing_slash {
+            path.push_str(separator);
+        }
+        Ok(path)
+    }
+}
+
+/// Converts each line of `input` with `convert`, which gets the path as written and parsed.
+/// Blank lines are left alone, and paths in double quotes stay quoted.
+fn convert_lines(
+    input: &str,
+    msys: bool,
+    convert: impl Fn(&str, &ParsedPath) -> anyhow::Result<String>,
+) -> anyhow::Result<String> {
+    let mut lines = vec![];
+    for line in input.split('\n') {
+        let (line, cr) = match line.strip_suffix('\r') {
+            Some(line) => (line, "\r"),
+            None => (line, ""),
+        };
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            lines.push(format!("{}{}", line, cr));
+            continue;
+        }
+        let (path, quoted) = match trimmed.strip_prefix('"').and_then(|p| p.strip_suffix('"')) {
+            Some(path) => (path, true),
+            None => (trimmed, false),
+        };
+        let converted =
+            convert(path, &parse(path, msys)?).map_err(|e| anyhow::anyhow!("{}: {}", path, e))?;
+        let start = line.len() - line.trim_start().len();
+        let end = start + trimmed.len();
+        let quote = if quoted { "\"" } else { "" };
+        lines.push(format!(
+            "{}{}{}{}{}{}",
+            &line[..start],
+            quote,
+            converted,
+            quote,
+            &line[end..],
+            cr
+        ));
+    }
+    Ok(lines.join("\n"))
+}
+
+/// Converts paths, one per line, to a particular flavor.
+pub struct ConvertPath {
+    pub flavor: Flavor,
+    /// Read `/c/...` as drive C, like MSYS and git bash do.
+    pub msys: bool,
+}
+
+impl ConvertPath {
+    pub fn new(flavor: Flavor) -> Self {
+        ConvertPath {
+            flavor,
+            msys: false,
+        }
+    }
+}
+
+impl Transform for ConvertPath {
+    fn name(&self) -> &str {
+        match self.flavor {
+            Flavor::Windows => "to windows path",
+            Flavor::Mixed => "to mixed path",
+            Flavor::Wsl => "to wsl path",
+            Flavor::Msys => "to msys path",
+            Flavor::FileUri => "to file uri",
+        }
+    }
+
+    fn description(&self) -> &str {
+        match self.flavor {
+            Flavor::Windows => "convert paths to windows paths, like C:\\Users",
+            Flavor::Mixed => "convert paths to windows paths with forward slashes, like C:/Users",
+            Flavor::Wsl => "convert paths to wsl paths, like /mnt/c/Users",
+            Flavor::Msys => "convert paths to msys and git bash paths, like /c/Users",
+            Flavor::FileUri => "convert paths to file uris, like file:///C:/Users",
+        }
+    }
+
+    fn default_key(&self) -> Option<&str> {
+        None
+    }
+
+    fn apply(&self, input: &str) -> anyhow::Result<String> {
+        convert_lines(input, self.msys, |_, path| path.write(self.flavor))
+    }
+
+    fn settings(&mut self) -> Vec<Setting<'_>> {
+        vec![Setting::Toggle {
+            label: "read /c/ as drive c",
+            value: &mut self.msys,
+        }]
+    }
+}
+
+const UNIX_FLAVORS: &[&str] = &["wsl", "msys", "mixed"];
+
+/// Converts Windows paths to a unix flavor, and everything else to Windows paths, so pressing it
+/// twice gets back to where it started. Posix paths that aren't on a drive, like `/etc/hosts`,
+/// only have their slashes flipped.
+#[derive(Default)]
+pub struct FlipPath {
+    /// An index into `UNIX_FLAVORS`.
+    pub unix: usize,
+}
+
+impl Transform for FlipPath {
+    fn name(&self) -> &str {
+        "flip path"
+    }
+
+    fn description(&self) -> &str {
+        "convert windows paths to unix paths and back"
+    }
+
+    fn default_key(&self) -> Option<&str> {
+        Some("D")
+    }
+
+    fn apply(&self, input: &str) -> anyhow::Result<String> {
+        let unix = match self.unix {
+            0 => Flavor::Wsl,
+            1 => Flavor::Msys,
+            _ => Flavor::Mixed,
+        };
+        // Only someone who uses MSYS paths means drive C by `/c/`.
+        convert_lines(input, unix == Flavor::Msys, |written, path| {
+            // Only backslashes make a path windows, so that mixed paths flip back too.
+            let windows = written.contains('\\') && !written.starts_with('/');
+            if windows && path.root == Root::None && written.starts_with('\\') {
+                // A rooted path without a drive, like `\etc\hosts`, came from flipping a posix one.
+                Ok(written.replace('\\', "/"))
+            } else if windows {
+                // WSL can't reach a share that isn't mounted, so shares get forward slashes.
+                path.write(unix).or_else(|_| path.write(Flavor::Mixed))
+            } else {
+                // Posix paths outside a mounted drive have no windows equivalent, so those just
+                // get their slashes flipped, the way this key always has.
+                Ok(path
+                    .write(Flavor::Windows)
+                    .unwrap_or_else(|_| written.replace('/', "\\")))
+            }
+        })
+    }
+
+    fn settings(&mut self) -> Vec<Setting<'_>> {
+        vec![Setting::Choice {
+            label: "unix flavor",
+            value: &mut self.unix,
+            options: UNIX_FLAVORS,
+        }]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn convert(input: &str, flavor: Flavor) -> String {
+        ConvertPath::new(flavor).apply(input).unwrap()
+    }
+
+    #[test]
+    fn converts_between_flavors() {
+        let flavors = [
+            (Flavor::Windows, r"C:\Users\me\a b"),
+            (Flavor::Mixed, "C:/Users/me/a b"),
+            (Flavor::Wsl, "/mnt/c/Users/me/a b"),
+            (Flavor::FileUri, "file:///C:/Users/me/a%20b"),
+        ];
+        for (_, from) in flavors {
+            for (flavor, to) in flavors {
+                assert_eq!(convert(from, flavor), to, "{} to {:?}", from, flavor);
+            }
+        }
+        let mut msys = ConvertPath::new(Flavor::Windows);
+        msys.msys = true;
+        assert_eq!(msys.apply("/c/Users/me/a b").unwrap(), r"C:\Users\me\a b");
+        assert_eq!(convert(r"C:\Users\me\a b", Flavor::Msys), "/c/Users/me/a b");
+    }
+
+    #[test]
+    fn unc_paths() {
+        assert_eq!(
+            convert(r"\\server\share\x", Flavor::Mixed),
+            "//server/share/x"
+        );
+        assert_eq!(
+            convert(r"\\?\UNC\server\share", Flavor::Windows),
+            r"\\server\share"
+        );
+        assert_eq!(
+            convert("file://server/share/x", Flavor::Windows),
+            r"\\server\share\x"
+        );
+        assert!(ConvertPath::new(Flavor::Wsl)
+            .apply(r"\\server\share")
+            .is_err());
+        assert!(parse(r"\\server", false).is_err());
+    }
+
+    #[test]
+    fn roots_and_relative_paths() {
+        assert_eq!(convert("C:", Flavor::Windows), r"C:\");
+        assert_eq!(convert("/mnt/d", Flavor::Windows), r"D:\");
+        assert_eq!(convert("/", Flavor::FileUri), "file:///");
+        assert_eq!(convert(r"src\main.rs", Flavor::Mixed), "src/main.rs");
+        assert_eq!(convert("C:/tmp/", Flavor::Windows), r"C:\tmp\");
+        assert!(ConvertPath::new(Flavor::FileUri).apply("src").is_err());
+        assert!(ConvertPath::new(Flavor::Windows)
+            .apply("/etc/hosts")
+            .is_err());
+    }
+
+    #[test]
+    fn non_ascii_paths_dont_panic() {
+        assert_eq!(
+            convert("日本語\\ファイル", Flavor::Mixed),
+            "日本語/ファイル"
+        );
+        assert_eq!(convert(r"\\?\日本\x", Flavor::Mixed), "日本/x");
+        assert_eq!(convert("ﬁle:", Flavor::Mixed), "ﬁle:");
+    }
+
+    #[test]
+    fn single_letter_directories_are_only_drives_for_msys() {
+        assert_eq!(parse("/c/x", false).unwrap(), split("/c/x", Root::Posix));
+        assert_eq!(FlipPath::default().apply("/c/x").unwrap(), r"\c\x");
+        let flip = FlipPath { unix: 1 };
+        assert_eq!(flip.apply("/c/x").unwrap(), r"C:\x");
+    }
+
+    #[test]
+    fn flip_swaps_slashes_in_posix_paths() {
+        let flip = FlipPath::default();
+        assert_eq!(flip.apply("/etc/hosts").unwrap(), r"\etc\hosts");
+        assert_eq!(flip.apply(r"\etc\hosts").unwrap(), "/etc/hosts");
+        assert_eq!(flip.apply("src/main.rs").unwrap(), r"src\main.rs");
+        assert_eq!(flip.apply(r"src\main.rs").unwrap(), "src/main.rs");
+    }
+
+    #[test]
+    fn flip_round_trips() {
+        let input = "  \"C:\\Program Files\\x\"\r\n\nD:\\y\\";
+        for unix in 0..UNIX_FLAVORS.len() {
+            let flip = FlipPath { unix };
+            let flipped = flip.apply(input).unwrap();
+            assert_eq!(flip.apply(&flipped).unwrap(), input, "{}", flipped);
+        }
+    }
+}
@@ -0,0 +1,354 @@
+//! A small subset of jq, plus the JSONPath spellings people reach for out of habit.
+//!
+//! Supported: `.`, `.key`, `."quoted key"`, `.[0]`, `.[-1]`, `.[2:4]`, `.[]`, `..` (every value,
+//! recursively), pipes, `keys` and `length`. JSONPath's `$`, `[*]`, `['key']` and `$..key` are
+//! accepted as the same things.
+
+use serde_json::Value;
+
+use crate::json;
+
+enum Step {
+    /// `lenient` fields skip values that aren't objects or don't have the key, rather than
+    /// failing or producing null. That's what `?` asks for, and what JSONPath's `$..key` means.
+    Field {
+        name: String,
+        lenient: bool,
+    },
+    Index(i64),
+    Slice(Option<i64>, Option<i64>),
+    Iterate,
+    Recurse,
+    Keys,
+    Length,
+}
+
+struct Parser<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+}
+
+impl<'a> Parser<'a> {
+    fn eat(&mut self, c: char) -> bool {
+        if self.chars.peek() == Some(&c) {
+            self.chars.next();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.chars.next_if(|c| c.is_whitespace()).is_some() {}
+    }
+
+    fn ident(&mut self) -> String {
+        let mut ident = String::new();
+        while let Some(c) = self.chars.next_if(|c| c.is_alphanumeric() || *c == '_') {
+            ident.push(c);
+        }
+        ident
+    }
+
+    fn string(&mut self, quote: char) -> anyhow::Result<String> {
+        let mut s = String::new();
+        loop {
+            match self.chars.next() {
+                Some(c) if c == quote => return Ok(s),
+                Some('\\') => match self.chars.next() {
+                    Some(c) => s.push(c),
+                    None => break,
+                },
+                Some(c) => s.push(c),
+                None => break,
+            }
+        }
+        anyhow::bail!("unterminated string")
+    }
+
+    fn int(&mut self) -> anyhow::Result<Option<i64>> {
+        let mut digits = String::new();
+        if self.eat('-') {
+            digits.push('-');
+        }
+        while let Some(c) = self.chars.next_if(|c| c.is_ascii_digit()) {
+            digits.push(c);
+        }
+        if digits.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(digits.parse()?))
+    }
+
+    /// Everything between `[` and `]`.
+    fn bracket(&mut self) -> anyhow::Result<Step> {
+        self.skip_whitespace();
+        let step = if self.eat(']') {
+            return Ok(Step::Iterate);
+        } else if self.eat('*') {
+            Step::Iterate
+        } else if self.eat('"') {
+            Step::Field {
+                name: self.string('"')?,
+                lenient: false,
+            }
+        } else if self.eat('\'') {
+            Step::Field {
+                name: self.string('\'')?,
+                lenient: false,
+            }
+        } else {
+            let start = self.int()?;
+            self.skip_whitespace();
+            if self.eat(':') {
+                self.skip_whitespace();
+                Step::Slice(start, self.int()?)
+            } else {
+                Step::Index(start.ok_or_else(|| anyhow::anyhow!("expected an index"))?)
+            }
+        };
+        self.skip_whitespace();
+        if !self.eat(']') {
+            anyhow::bail!("expected ]");
+        }
+        Ok(step)
+    }
+
+    fn parse(&mut self) -> anyhow::Result<Vec<Step>> {
+        let mut steps = vec![];
+        loop {
+            self.skip_whitespace();
+            match self.chars.peek() {
+                None => return Ok(steps),
+                Some('|') => {
+                    self.chars.next();
+                }
+                Some('$') => {
+                    self.chars.next();
+                }
+                Some('.') => {
+                    self.chars.next();
+                    if self.eat('.') {
+                        steps.push(Step::Recurse);
+                        // `$..key`: the key applies to every value found.
+                        let name = self.ident();
+                        if !name.is_empty() {
+                            steps.push(Step::Field {
+                                name,
+                                lenient: true,
+                            });
+                        }
+                    } else if self.eat('"') {
+                        steps.push(Step::Field {
+                            name: self.string('"')?,
+                            lenient: false,
+                        });
+                    } else if self.eat('[') {
+                        steps.push(self.bracket()?);
+                    } else {
+                        let name = self.ident();
+                        if !name.is_empty() {
+                            steps.push(Step::Field {
+                                name,
+                                lenient: false,
+                            });
+                        }
+                    }
+                }
+                Some('[') => {
+                    self.chars.next();
+                    steps.push(self.bracket()?);
+                }
+                Some('?') => {
+                    self.chars.next();
+                    if let Some(Step::Field { lenient, .. }) = steps.last_mut() {
+                        *lenient = true;
+                    }
+                }
+                Some(c) if c.is_alphabetic() => match self.ident().as_str() {
+                    "keys" => steps.push(Step::Keys),
+                    "length" => steps.push(Step::Length),
+                    other => anyhow::bail!("unknown function {}", other),
+                },
+                Some(c) => anyhow::bail!("unexpected {:?}", c),
+            }
+        }
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// Resolves a possibly negative index against `len`, python-style.
+fn resolve(index: i64, len: usize) -> usize {
+    if index < 0 {
+        len.saturating_sub(index.unsigned_abs() as usize)
+    } else {
+        (index as usize).min(len)
+    }
+}
+
+fn recurse(value: Value, out: &mut Vec<Value>) {
+    out.push(value.clone());
+    match value {
+        Value::Array(items) => items.into_iter().for_each(|v| recurse(v, out)),
+        Value::Object(map) => map.into_iter().for_each(|(_, v)| recurse(v, out)),
+        _ => (),
+    }
+}
+
+fn eval(step: &Step, value: Value, out: &mut Vec<Value>) -> anyhow::Result<()> {
+    match (step, value) {
+        (Step::Field { name, lenient }, Value::Object(mut map)) => match map.remove(name) {
+            Some(v) => out.push(v),
+            None if !lenient => out.push(Value::Null),
+            None => (),
+        },
+        (Step::Field { lenient: true, .. }, _) => (),
+        (Step::Field { .. } | Step::Index(_) | Step::Slice(..), Value::Null) => {
+            out.push(Value::Null)
+        }
+        (Step::Index(index), Value::Array(items)) => {
+            let len = items.len() as i64;
+            let index = if *index < 0 { len + index } else { *index };
+            out.push(if (0..len).contains(&index) {
+                items[index as usize].clone()
+            } else {
+                Value::Null
+            });
+        }
+        (Step::Slice(start, end), Value::Array(items)) => {
+            let start = resolve(start.unwrap_or(0), items.len());
+            let end = resolve(end.unwrap_or(items.len() as i64), items.len()).max(start);
+            out.push(Value::Array(items[start..end].to_vec()));
+        }
+        (Step::Slice(start, end), Value::String(s)) => {
+            let chars: Vec<char> = s.chars().collect();
+            let start = resolve(start.unwrap_or(0), chars.len());
+            let end = resolve(end.unwrap_or(chars.len() as i64), chars.len()).max(start);
+            out.push(Value::String(chars[start..end].iter().collect()));
+        }
+        (Step::Iterate, Value::Array(items)) => out.extend(items),
+        (Step::Iterate, Value::Object(map)) => out.extend(map.into_iter().map(|(_, v)| v)),
+        (Step::Recurse, value) => recurse(value, out),
+        (Step::Keys, Value::Object(map)) => {
+            let mut keys: Vec<String> = map.into_iter().map(|(k, _)| k).collect();
+            keys.sort();
+            out.push(keys.into());
+        }
+        (Step::Keys, Value::Array(items)) => out.push((0..items.len()).collect::<Vec<_>>().into()),
+        (Step::Length, Value::Array(items)) => out.push(items.len().into()),
+        (Step::Length, Value::Object(map)) => out.push(map.len().into()),
+        (Step::Length, Value::String(s)) => out.push(s.chars().count().into()),
+        (Step::Length, Value::Null) => out.push(0.into()),
+        // The absolute value, staying an integer if it was one.
+        (Step::Length, Value::Number(n)) => out.push(match (n.as_i64(), n.as_u64()) {
+            (Some(i), _) => i.unsigned_abs().into(),
+            (_, Some(u)) => u.into(),
+            _ => n.as_f64().unwrap_or_default().abs().into(),
+        }),
+        (Step::Field { name, .. }, value) => {
+            anyhow::bail!("cannot index {} with {:?}", type_name(&value), name)
+        }
+        (Step::Index(_) | Step::Slice(..), value) => {
+            anyhow::bail!("cannot index {} with a number", type_name(&value))
+        }
+        (Step::Iterate, value) => anyhow::bail!("cannot iterate over {}", type_name(&value)),
+        (Step::Keys, value) => anyhow::bail!("{} has no keys", type_name(&value)),
+        (Step::Length, value) => anyhow::bail!("{} has no length", type_name(&value)),
+    }
+    Ok(())
+}
+
+/// Runs `query` against the JSON document in `input`, returning every value it produces.
+pub fn run(query: &str, input: &str) -> anyhow::Result<Vec<Value>> {
+    let steps = Parser {
+        chars: query.chars().peekable(),
+    }
+    .parse()?;
+    let mut values = vec![json::parse(input)?];
+    for step in &steps {
+        let mut out = vec![];
+        for value in values {
+            eval(step, value, &mut out)?;
+        }
+        values = out;
+    }
+    Ok(values)
+}
+
+/// One result per line like jq. With `raw`, strings are written without quotes.
+pub fn format(values: &[Value], raw: bool) -> anyhow::Result<String> {
+    let mut lines = vec![];
+    for value in values {
+        lines.push(match value {
+            Value::String(s) if raw => s.clone(),
+            _ => serde_json::to_string_pretty(value)?,
+        });
+    }
+    Ok(lines.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DOC: &str = r#"{"items": [{"id": 1, "tags": ["a"]}, {"id": 2}], "name": "x", "n": -3}"#;
+
+    fn query(query: &str, input: &str) -> String {
+        format(&run(query, input).unwrap(), false)
+            .unwrap()
+            .replace(char::is_whitespace, "")
+    }
+
+    #[test]
+    fn paths() {
+        assert_eq!(query(".items[].id", DOC), "12");
+        assert_eq!(query(".items[-1]", DOC), r#"{"id":2}"#);
+        assert_eq!(query(".items[5]", DOC), "null");
+        assert_eq!(query(r#"."name""#, DOC), r#""x""#);
+        assert_eq!(query(".items[0:1] | length", DOC), "1");
+        assert_eq!(query(".name[0:1]", r#"{"name": "héllo"}"#), r#""h""#);
+        assert_eq!(query(".missing.deeper", DOC), "null");
+    }
+
+    #[test]
+    fn jsonpath_spellings() {
+        assert_eq!(query("$.items[*].id", DOC), query(".items[].id", DOC));
+        assert_eq!(query("$['name']", DOC), r#""x""#);
+        assert_eq!(query("$..id", DOC), "12");
+    }
+
+    #[test]
+    fn functions_match_jq() {
+        assert_eq!(query(".n | length", DOC), "3");
+        assert_eq!(query("length", "-2.5"), "2.5");
+        assert_eq!(query("keys", DOC), r#"["items","n","name"]"#);
+        assert_eq!(query(".[1:2]", "null"), "null");
+        assert_eq!(query(".missing | length", DOC), "0");
+    }
+
+    #[test]
+    fn errors() {
+        assert!(run(".name[0]", DOC).is_err());
+        assert!(run(".n[]", DOC).is_err());
+        assert!(run("true | length", "true").is_err());
+        assert!(run("nope", DOC).is_err());
+        assert!(run(".[0", DOC).is_err());
+    }
+
+    #[test]
+    fn raw_strings() {
+        let values = run(".items[0].tags", r#"{"items": [{"tags": ["a b"]}]}"#).unwrap();
+        assert_eq!(format(&values, true).unwrap(), "[\n  \"a b\"\n]");
+        let values = run(".items[0].tags[]", r#"{"items": [{"tags": ["a b"]}]}"#).unwrap();
+        assert_eq!(format(&values, true).unwrap(), "a b");
+    }
+}
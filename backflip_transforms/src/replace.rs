@@ -0,0 +1,137 @@
+use ::regex::Regex;
+
+use crate::Transform;
+
+/// One find/replace pair. The replacement can refer to capture groups as `$1` or `$name`
+/// (`${name}` when it's followed by more letters), and `$$` is a literal dollar sign.
+pub struct Rule {
+    pub pattern: Regex,
+    pub replacement: String,
+}
+
+impl Rule {
+    pub fn new(pattern: &str, replacement: &str) -> anyhow::Result<Self> {
+        Ok(Rule {
+            pattern: Regex::new(pattern)?,
+            replacement: replacement.to_string(),
+        })
+    }
+}
+
+/// Regex replacements that run as a transform. Rules are applied together in a single pass, so
+/// one rule never sees the output of another: `/` -> `\` and `\` -> `/` swaps the two. Where
+/// rules match at the same place, the first one wins.
+pub struct Replace {
+    pub name: String,
+    pub description: String,
+    pub key: Option<String>,
+    pub rules: Vec<Rule>,
+}
+
+impl Replace {
+    pub fn new(name: &str, rules: Vec<Rule>) -> Self {
+        Replace {
+            name: name.to_string(),
+            description: name.to_string(),
+            key: None,
+            rules,
+        }
+    }
+}
+
+impl Transform for Replace {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn default_key(&self) -> Option<&str> {
+        self.key.as_deref()
+    }
+
+    fn apply(&self, input: &str) -> anyhow::Result<String> {
+        let mut out = String::with_capacity(input.len());
+        let mut pos = 0;
+        let mut last_end = None;
+        while pos <= input.len() {
+            let earliest = self
+                .rules
+                .iter()
+                .filter_map(|rule| Some((rule, rule.pattern.captures_at(input, pos)?)))
+                .min_by_key(|(_, captures)| captures.get(0).unwrap().start());
+            let Some((rule, captures)) = earliest else {
+                break;
+            };
+            let found = captures.get(0).unwrap();
+            out.push_str(&input[pos..found.start()]);
+            pos = found.end();
+            // Like `Regex::replace_all`, an empty match right after another match doesn't count.
+            if !(found.is_empty() && last_end == Some(found.start())) {
+                captures.expand(&rule.replacement, &mut out);
+                last_end = Some(found.end());
+            }
+            if found.is_empty() {
+                // Step over a character so an empty match can't repeat forever.
+                match input[pos..].chars().next() {
+                    Some(c) => {
+                        out.push(c);
+                        pos += c.len_utf8();
+                    }
+                    None => return Ok(out),
+                }
+            }
+        }
+        out.push_str(&input[pos.min(input.len())..]);
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn replace(rules: &[(&str, &str)], input: &str) -> String {
+        let rules = rules
+            .iter()
+            .map(|(pattern, replacement)| Rule::new(pattern, replacement).unwrap())
+            .collect();
+        Replace::new("test", rules).apply(input).unwrap()
+    }
+
+    #[test]
+    fn expands_captures() {
+        let rules = [(r"(?<key>\w+)=(?<value>\w+)", "$value=$key")];
+        assert_eq!(replace(&rules, "a=1, b=2"), "1=a, 2=b");
+        assert_eq!(replace(&[(r"(\d+)", "${1}px $$")], "w 10"), "w 10px $");
+    }
+
+    #[test]
+    fn rules_apply_in_one_pass() {
+        let rules = [("/", r"\"), (r"\\", "/")];
+        assert_eq!(replace(&rules, r"a/b\c"), r"a\b/c");
+        // The first rule wins when two match at the same place.
+        assert_eq!(replace(&[("ab", "1"), ("a", "2")], "aab"), "21");
+    }
+
+    #[test]
+    fn empty_matches_match_replace_all() {
+        for (pattern, input) in [("x*", "axxb"), ("", "héllo"), (r"\b", "a b")] {
+            let regex = Regex::new(pattern).unwrap();
+            assert_eq!(
+                replace(&[(pattern, "-")], input),
+                regex.replace_all(input, "-"),
+                "{:?} on {:?}",
+                pattern,
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn bad_patterns_fail_to_compile() {
+        assert!(Rule::new("(", "").is_err());
+    }
+}
@@ -0,0 +1,409 @@
+use std::collections::{HashSet, VecDeque};
+
+use serde_json::Value;
+
+use crate::{case, json, Setting, Transform};
+
+/// Strict and reserved keywords, all of which need to be raw identifiers.
+const KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "crate",
+    "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "if", "impl", "in",
+    "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref",
+    "return", "static", "struct", "trait", "true", "try", "type", "typeof", "unsafe", "unsized",
+    "use", "virtual", "where", "while", "yield",
+];
+
+/// Type names the generated code already uses, or that the prelude gives a meaning, which a
+/// struct of the same name would shadow.
+const TAKEN_TYPE_NAMES: &[&str] = &[
+    "Self",
+    "String",
+    "Option",
+    "Vec",
+    "Box",
+    "Result",
+    "Serialize",
+    "Deserialize",
+];
+
+/// The inferred type of a JSON value, merged across every sample of it.
+#[derive(Clone)]
+enum Shape {
+    /// Only ever seen as an empty array's element, so nothing is known about it.
+    Unknown,
+    Null,
+    Bool,
+    Int,
+    Float,
+    String,
+    Array(Box<Shape>),
+    Object(Vec<Field>),
+    Nullable(Box<Shape>),
+    /// Samples disagreed in a way no Rust type captures, so fall back to `serde_json::Value`.
+    Any,
+}
+
+#[derive(Clone)]
+struct Field {
+    key: String,
+    shape: Shape,
+    /// Missing from at least one of the objects that were merged.
+    optional: bool,
+}
+
+fn infer(value: &Value) -> Shape {
+    match value {
+        Value::Null => Shape::Null,
+        Value::Bool(_) => Shape::Bool,
+        Value::Number(n) if n.is_i64() => Shape::Int,
+        Value::Number(_) => Shape::Float,
+        Value::String(_) => Shape::String,
+        Value::Array(items) => Shape::Array(Box::new(
+            items
+                .iter()
+                .map(infer)
+                .reduce(merge)
+                .unwrap_or(Shape::Unknown),
+        )),
+        Value::Object(map) => Shape::Object(
+            map.iter()
+                .map(|(key, value)| Field {
+                    key: key.clone(),
+                    shape: infer(value),
+                    optional: false,
+                })
+                .collect(),
+        ),
+    }
+}
+
+fn merge(a: Shape, b: Shape) -> Shape {
+    match (a, b) {
+        (Shape::Unknown, s) | (s, Shape::Unknown) => s,
+        (Shape::Null, Shape::Null) => Shape::Null,
+        (Shape::Null, s) | (s, Shape::Null) => match s {
+            Shape::Nullable(_) => s,
+            s => Shape::Nullable(Box::new(s)),
+        },
+        (Shape::Nullable(a), Shape::Nullable(b)) => Shape::Nullable(Box::new(merge(*a, *b))),
+        (Shape::Nullable(a), b) | (b, Shape::Nullable(a)) => {
+            Shape::Nullable(Box::new(merge(*a, b)))
+        }
+        (Shape::Bool, Shape::Bool) => Shape::Bool,
+        (Shape::Int, Shape::Int) => Shape::Int,
+        (Shape::Int | Shape::Float, Shape::Int | Shape::Float) => Shape::Float,
+        (Shape::String, Shape::String) => Shape::String,
+        (Shape::Array(a), Shape::Array(b)) => Shape::Array(Box::new(merge(*a, *b))),
+        (Shape::Object(a), Shape::Object(b)) => Shape::Object(merge_fields(a, b)),
+        _ => Shape::Any,
+    }
+}
+
+fn merge_fields(a: Vec<Field>, mut b: Vec<Field>) -> Vec<Field> {
+    let mut merged = vec![];
+    for field in a {
+        match b.iter().position(|f| f.key == field.key) {
+            Some(index) => {
+                let other = b.remove(index);
+                merged.push(Field {
+                    key: field.key,
+                    shape: merge(field.shape, other.shape),
+                    optional: field.optional || other.optional,
+                });
+            }
+            None => merged.push(Field {
+                optional: true,
+                ..field
+            }),
+        }
+    }
+    merged.extend(b.into_iter().map(|f| Field {
+        optional: true,
+        ..f
+    }));
+    merged
+}
+
+/// A snake_case field name for `key`, and whether it needs `#[serde(rename)]`.
+fn field_name(key: &str) -> (String, bool) {
+    let mut name = case::to_snake(key);
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        name = format!("field_{}", name);
+    }
+    if matches!(name.as_str(), "self" | "super" | "crate") {
+        // These can't be raw identifiers.
+        return (format!("{}_", name), true);
+    }
+    if KEYWORDS.contains(&name.as_str()) {
+        // serde strips the `r#` itself, so raw identifiers don't need renaming.
+        return (format!("r#{}", name), name != key);
+    }
+    let rename = name != key;
+    (name, rename)
+}
+
+/// Array element structs are named after the singular of their field, e.g. `items` -> `Item` and
+/// `classes` -> `Class`. Other plurals ending in -ses could have either a -s or a -se singular
+/// (`statuses`, `responses`), so those get an `Item` suffix instead of a guess. Names like
+/// `String` or `Option` get a `Struct` suffix so they don't shadow the types the fields use.
+fn struct_name(key: &str, element: bool) -> String {
+    let mut name = case::to_pascal(key);
+    if element && name.len() > 1 {
+        if ["sses", "xes", "ches", "shes", "zzes"]
+            .iter()
+            .any(|suffix| name.ends_with(suffix))
+        {
+            name.truncate(name.len() - 2);
+        } else if name.ends_with("ses") {
+            name.push_str("Item");
+        } else if name.ends_with('s') && !name.ends_with("ss") {
+            name.pop();
+        }
+    }
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        name = format!("Struct{}", name);
+    }
+    if TAKEN_TYPE_NAMES.contains(&name.as_str()) {
+        name.push_str("Struct");
+    }
+    name
+}
+
+struct Generator {
+    used_names: HashSet<String>,
+    /// Structs that still need writing out: (name, fields).
+    pending: VecDeque<(String, Vec<Field>)>,
+}
+
+impl Generator {
+    fn unique_name(&mut self, name: String) -> String {
+        let mut unique = name.clone();
+        let mut n = 2;
+        while !self.used_names.insert(unique.clone()) {
+            unique = format!("{}{}", name, n);
+            n += 1;
+        }
+        unique
+    }
+
+    /// The Rust type for `shape`, queueing up a struct for it if it's an object.
+    fn type_for(&mut self, shape: Shape, key: &str, element: bool) -> String {
+        match shape {
+            Shape::Unknown | Shape::Any => "serde_json::Value".to_string(),
+            Shape::Null => "Option<serde_json::Value>".to_string(),
+            Shape::Bool => "bool".to_string(),
+            Shape::Int => "i64".to_string(),
+            Shape::Float => "f64".to_string(),
+            Shape::String => "String".to_string(),
+            Shape::Array(element_shape) => {
+                format!("Vec<{}>", self.type_for(*element_shape, key, true))
+            }
+            Shape::Nullable(inner) => format!("Option<{}>", self.type_for(*inner, key, element)),
+            Shape::Object(fields) => {
+                let name = self.unique_name(struct_name(key, element));
+                self.pending.push_back((name.clone(), fields));
+                name
+            }
+        }
+    }
+
+    fn write_struct(&mut self, name: &str, fields: Vec<Field>, out: &mut String) {
+        out.push_str("#[derive(Serialize, Deserialize)]\n");
+        out.push_str(&format!("pub struct {} {{\n", name));
+        let mut used_fields = HashSet::new();
+        for field in fields {
+            let (mut field_name, mut rename) = field_name(&field.key);
+            // Keys like `userId` and `user_id` both come out as `user_id`.
+            if !used_fields.insert(field_name.clone()) {
+                let base = field_name.trim_start_matches("r#").to_string();
+                let mut n = 2;
+                while !used_fields.insert(format!("{}_{}", base, n)) {
+                    n += 1;
+                }
+                field_name = format!("{}_{}", base, n);
+                rename = true;
+            }
+            let mut ty = self.type_for(field.shape.clone(), &field.key, false);
+            if field.optional && !ty.starts_with("Option<") {
+                ty = format!("Option<{}>", ty);
+            }
+            if rename {
+                out.push_str(&format!("    #[serde(rename = {:?})]\n", field.key));
+            }
+            out.push_str(&format!("    pub {}: {},\n", field_name, ty));
+        }
+        out.push_str("}\n");
+    }
+}
+
+/// Infers a schema from sample JSON and writes it out as serde structs.
+pub struct JsonToRustStructs {
+    pub root_name: String,
+}
+
+impl Default for JsonToRustStructs {
+    fn default() -> Self {
+        JsonToRustStructs {
+            root_name: "Root".to_string(),
+        }
+    }
+}
+
+impl Transform for JsonToRustStructs {
+    fn name(&self) -> &str {
+        "json to rust structs"
+    }
+
+    fn description(&self) -> &str {
+        "generate serde structs from a sample json payload"
+    }
+
+    fn default_key(&self) -> Option<&str> {
+        None
+    }
+
+    fn apply(&self, input: &str) -> anyhow::Result<String> {
+        // A top-level array is described by its element type.
+        let fields = match infer(&json::parse(input)?) {
+            Shape::Object(fields) => fields,
+            Shape::Array(element) => match *element {
+                Shape::Object(fields) => fields,
+                _ => anyhow::bail!("expected an array of objects"),
+            },
+            _ => anyhow::bail!("expected an object or an array of objects"),
+        };
+
+        let mut generator = Generator {
+            used_names: HashSet::new(),
+            pending: VecDeque::new(),
+        };
+        let root = generator.unique_name(struct_name(&self.root_name, false));
+        generator.pending.push_back((root, fields));
+
+        let mut out = "use serde::{Deserialize, Serialize};\n".to_string();
+        while let Some((name, fields)) = generator.pending.pop_front() {
+            out.push('\n');
+            generator.write_struct(&name, fields, &mut out);
+        }
+        Ok(out)
+    }
+
+    fn settings(&mut self) -> Vec<Setting<'_>> {
+        vec![Setting::Text {
+            label: "root struct",
+            value: &mut self.root_name,
+        }]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn structs(input: &str) -> String {
+        JsonToRustStructs::default().apply(input).unwrap()
+    }
+
+    #[test]
+    fn infers_nested_structs() {
+        let out = structs(r#"{"id": 1, "ratio": 0.5, "tags": ["a"], "owner": {"name": "x"}}"#);
+        assert_eq!(
+            out,
+            "use serde::{Deserialize, Serialize};\n\n\
+             #[derive(Serialize, Deserialize)]\n\
+             pub struct Root {\n    \
+             pub id: i64,\n    \
+             pub ratio: f64,\n    \
+             pub tags: Vec<String>,\n    \
+             pub owner: Owner,\n\
+             }\n\n\
+             #[derive(Serialize, Deserialize)]\n\
+             pub struct Owner {\n    \
+             pub name: String,\n\
+             }\n"
+        );
+    }
+
+    #[test]
+    fn merges_samples() {
+        let out = structs(r#"[{"a": 1, "b": null}, {"a": 2.5, "b": "x", "c": true}, {"a": "y"}]"#);
+        assert!(out.contains("pub a: serde_json::Value,"), "{}", out);
+        assert!(out.contains("pub b: Option<String>,"), "{}", out);
+        assert!(out.contains("pub c: Option<bool>,"), "{}", out);
+        assert!(JsonToRustStructs::default().apply("[1, 2]").is_err());
+    }
+
+    #[test]
+    fn renames_awkward_keys() {
+        let out =
+            structs(r#"{"userId": 1, "user_id": 2, "type": 3, "final": 4, "self": 5, "2x": 6}"#);
+        assert!(
+            out.contains("#[serde(rename = \"userId\")]\n    pub user_id: i64,"),
+            "{}",
+            out
+        );
+        assert!(
+            out.contains("#[serde(rename = \"user_id\")]\n    pub user_id_2: i64,"),
+            "{}",
+            out
+        );
+        assert!(out.contains("    pub r#type: i64,"), "{}", out);
+        assert!(out.contains("    pub r#final: i64,"), "{}", out);
+        assert!(
+            out.contains("#[serde(rename = \"self\")]\n    pub self_: i64,"),
+            "{}",
+            out
+        );
+        assert!(out.contains("pub field_2x: i64,"), "{}", out);
+    }
+
+    #[test]
+    fn element_structs_are_singular() {
+        assert_eq!(struct_name("items", true), "Item");
+        assert_eq!(struct_name("matches", true), "Match");
+        assert_eq!(struct_name("classes", true), "Class");
+        assert_eq!(struct_name("statuses", true), "StatusesItem");
+        assert_eq!(struct_name("aliases", true), "AliasesItem");
+        assert_eq!(struct_name("address", true), "Address");
+        assert_eq!(struct_name("items", false), "Items");
+    }
+
+    #[test]
+    fn struct_names_dont_shadow_std_types() {
+        assert_eq!(struct_name("boxes", true), "BoxStruct");
+        assert_eq!(struct_name("self", false), "SelfStruct");
+        let out = structs(r#"{"string": {"a": "x"}, "options": [{"b": null}]}"#);
+        assert!(out.contains("pub string: StringStruct,"), "{}", out);
+        assert!(
+            out.contains("pub struct StringStruct {\n    pub a: String,"),
+            "{}",
+            out
+        );
+        assert!(out.contains("pub options: Vec<OptionStruct>,"), "{}", out);
+        assert!(
+            out.contains("pub struct OptionStruct {\n    pub b: Option<serde_json::Value>,"),
+            "{}",
+            out
+        );
+        let named = JsonToRustStructs {
+            root_name: "Vec".to_string(),
+        };
+        assert!(named
+            .apply("{}")
+            .unwrap()
+            .contains("pub struct VecStruct {"));
+    }
+
+    #[test]
+    fn nullable_samples_merge_to_one_option() {
+        let out = structs(r#"[{"a": [null, 1]}, {"a": [2, null]}]"#);
+        assert!(out.contains("pub a: Vec<Option<i64>>,"), "{}", out);
+    }
+
+    #[test]
+    fn struct_names_are_unique() {
+        let out = structs(r#"{"a": {"item": {"x": 1}}, "item": {"y": 2}}"#);
+        assert!(out.contains("pub struct Item {"), "{}", out);
+        assert!(out.contains("pub struct Item2 {"), "{}", out);
+    }
+}
@@ -0,0 +1,568 @@
+use std::sync::OnceLock;
+
+use ::regex::Regex;
+use serde_json::{Map, Value};
+
+use crate::{json, Setting, Transform};
+
+/// Delimiters `detect_delimiter` considers, in order of preference when they tie.
+const DELIMITERS: &[u8] = b"\t,;|";
+
+const DELIMITER_NAMES: &[&str] = &["comma", "tab", "semicolon"];
+
+fn read(input: &str, delimiter: u8) -> anyhow::Result<Vec<Vec<String>>> {
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(input.as_bytes());
+    let mut rows = vec![];
+    for record in reader.records() {
+        let record = record?;
+        rows.push(record.iter().map(str::to_string).collect());
+    }
+    Ok(rows)
+}
+
+/// Picks the delimiter that splits the first few rows into the same number of columns, and the
+/// most of them. Spreadsheets put tabs on the clipboard, so tabs win ties.
+fn detect_delimiter(input: &str) -> u8 {
+    let sample: String = input.lines().take(20).collect::<Vec<_>>().join("\n");
+    let mut best = (b'\t', 1);
+    for &delimiter in DELIMITERS {
+        let Ok(rows) = read(&sample, delimiter) else {
+            continue;
+        };
+        // The last sampled row might be cut off partway through a quoted field.
+        let rows = match rows.len() {
+            0 | 1 => &rows[..],
+            n => &rows[..n - 1],
+        };
+        let Some(columns) = rows.first().map(Vec::len) else {
+            continue;
+        };
+        if rows.iter().all(|row| row.len() == columns) && columns > best.1 {
+            best = (delimiter, columns);
+        }
+    }
+    best.0
+}
+
+/// Parses CSV or TSV, working out the delimiter. Short rows are padded to the width of the
+/// widest so every row has a cell for every column.
+pub fn parse(input: &str) -> anyhow::Result<Vec<Vec<String>>> {
+    let mut rows = read(input, detect_delimiter(input))?;
+    let columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+    if rows.is_empty() || columns == 0 {
+        anyhow::bail!("there's no table in the buffer");
+    }
+    for row in &mut rows {
+        row.resize(columns, String::new());
+    }
+    Ok(rows)
+}
+
+fn is_number(cell: &str) -> bool {
+    !cell.is_empty() && cell.trim().parse::<f64>().is_ok_and(f64::is_finite)
+}
+
+/// Whether every cell below the header in `column` is a number (or empty).
+fn numeric_column(rows: &[Vec<String>], column: usize) -> bool {
+    let mut cells = rows[1..].iter().map(|row| row[column].as_str());
+    rows.len() > 1 && cells.all(|cell| cell.is_empty() || is_number(cell))
+}
+
+fn write(rows: &[Vec<String>], delimiter: u8) -> anyhow::Result<String> {
+    let mut writer = csv::WriterBuilder::new()
+        .delimiter(delimiter)
+        .terminator(csv::Terminator::Any(b'\n'))
+        .from_writer(vec![]);
+    for row in rows {
+        writer.write_record(row)?;
+    }
+    let mut out = String::from_utf8(writer.into_inner()?)?;
+    out.pop();
+    Ok(out)
+}
+
+/// Converts CSV or TSV to a Markdown table, padded so the columns line up in plain text too.
+pub struct TableToMarkdown;
+
+impl Transform for TableToMarkdown {
+    fn name(&self) -> &str {
+        "table to markdown"
+    }
+
+    fn description(&self) -> &str {
+        "convert csv or tsv to a markdown table"
+    }
+
+    fn default_key(&self) -> Option<&str> {
+        None
+    }
+
+    fn apply(&self, input: &str) -> anyhow::Result<String> {
+        let rows: Vec<Vec<String>> = parse(input)?
+            .into_iter()
+            .map(|row| {
+                row.into_iter()
+                    .map(|cell| {
+                        cell.replace('|', "\\|")
+                            .replace("\r\n", "<br>")
+                            .replace('\n', "<br>")
+                    })
+                    .collect()
+            })
+            .collect();
+        let columns = rows[0].len();
+        let right: Vec<bool> = (0..columns).map(|c| numeric_column(&rows, c)).collect();
+        // At least three dashes, which some renderers need.
+        let widths: Vec<usize> = (0..columns)
+            .map(|c| {
+                rows.iter()
+                    .map(|row| row[c].chars().count())
+                    .max()
+                    .unwrap_or(0)
+                    .max(3)
+            })
+            .collect();
+
+        let line = |cells: Vec<String>| format!("| {} |", cells.join(" | "));
+        let pad = |row: &Vec<String>| {
+            line(
+                (0..columns)
+                    .map(|c| {
+                        if right[c] {
+                            format!("{:>1$}", row[c], widths[c])
+                        } else {
+                            format!("{:<1$}", row[c], widths[c])
+                        }
+                    })
+                    .collect(),
+            )
+        };
+        let mut lines = vec![pad(&rows[0])];
+        lines.push(line(
+            (0..columns)
+                .map(|c| {
+                    if right[c] {
+                        format!("{}:", "-".repeat(widths[c] - 1))
+                    } else {
+                        "-".repeat(widths[c])
+                    }
+                })
+                .collect(),
+        ));
+        lines.extend(rows[1..].iter().map(pad));
+        Ok(lines.join("\n"))
+    }
+}
+
+/// Turns a cell into a json value, guessing at its type if `infer` is set.
+fn cell_value(cell: &str, infer: bool) -> Value {
+    if !infer {
+        return Value::String(cell.to_string());
+    }
+    match cell {
+        "" => Value::Null,
+        "true" | "TRUE" => Value::Bool(true),
+        "false" | "FALSE" => Value::Bool(false),
+        cell => serde_json::from_str::<serde_json::Number>(cell.trim())
+            .map_or_else(|_| Value::String(cell.to_string()), Value::Number),
+    }
+}
+
+/// Converts CSV or TSV to an array of objects keyed by the header row.
+#[derive(Default)]
+pub struct TableToJson {
+    /// Turn numbers and booleans into json numbers and booleans, and empty cells into null.
+    pub infer_types: bool,
+}
+
+impl Transform for TableToJson {
+    fn name(&self) -> &str {
+        "table to json"
+    }
+
+    fn description(&self) -> &str {
+        "convert csv or tsv to json objects keyed by the header row"
+    }
+
+    fn default_key(&self) -> Option<&str> {
+        None
+    }
+
+    fn apply(&self, input: &str) -> anyhow::Result<String> {
+        let rows = parse(input)?;
+        let records: Vec<Value> = rows[1..]
+            .iter()
+            .map(|row| {
+                let mut record = Map::new();
+                for (header, cell) in rows[0].iter().zip(row) {
+                    record.insert(header.clone(), cell_value(cell, self.infer_types));
+                }
+                Value::Object(record)
+            })
+            .collect();
+        Ok(serde_json::to_string_pretty(&records)?)
+    }
+
+    fn settings(&mut self) -> Vec<Setting<'_>> {
+        vec![Setting::Toggle {
+            label: "detect numbers and booleans",
+            value: &mut self.infer_types,
+        }]
+    }
+}
+
+fn identifier_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| Regex::new(r"^[A-Za-z_][A-Za-z0-9_]*$").unwrap())
+}
+
+/// Quotes an identifier for SQL, unless it's a plain word that doesn't need it.
+fn sql_identifier(name: &str) -> String {
+    if identifier_regex().is_match(name) {
+        name.to_string()
+    } else {
+        format!("\"{}\"", name.replace('"', "\"\""))
+    }
+}
+
+/// Whether a cell can go in SQL as a bare number and come back out as the same text. Zip codes
+/// and phone numbers with leading zeros or a `+`, and ids too long for an i64 or f64, can't.
+fn sql_number(cell: &str) -> bool {
+    let cell = cell.trim();
+    let unsigned = cell.strip_prefix('-').unwrap_or(cell);
+    let mantissa = unsigned.split(['e', 'E']).next().unwrap_or(unsigned);
+    let leading_zero =
+        mantissa.starts_with('0') && mantissa[1..].starts_with(|c: char| c.is_ascii_digit());
+    if !is_number(cell)
+        || !unsigned.starts_with(|c: char| c.is_ascii_digit() || c == '.')
+        || leading_zero
+    {
+        return false;
+    }
+    if mantissa == unsigned && !mantissa.contains('.') {
+        cell.parse::<i64>().is_ok()
+    } else {
+        // f64 holds any 15 significant decimal digits exactly.
+        mantissa
+            .trim_start_matches(['0', '.'])
+            .bytes()
+            .filter(u8::is_ascii_digit)
+            .count()
+            <= 15
+    }
+}
+
+fn sql_value(cell: &str) -> String {
+    if cell.is_empty() {
+        "NULL".to_string()
+    } else if sql_number(cell) {
+        cell.trim().to_string()
+    } else {
+        format!("'{}'", cell.replace('\'', "''"))
+    }
+}
+
+/// Converts CSV or TSV to one `INSERT` statement with a row of values per line. Numbers are
+/// left unquoted, unless quoting is the only way to keep them as written, and empty cells
+/// become `NULL`.
+pub struct TableToSql {
+    pub table: String,
+}
+
+impl Default for TableToSql {
+    fn default() -> Self {
+        TableToSql {
+            table: "table_name".to_string(),
+        }
+    }
+}
+
+impl Transform for TableToSql {
+    fn name(&self) -> &str {
+        "table to sql insert"
+    }
+
+    fn description(&self) -> &str {
+        "convert csv or tsv to an sql insert statement"
+    }
+
+    fn default_key(&self) -> Option<&str> {
+        None
+    }
+
+    fn apply(&self, input: &str) -> anyhow::Result<String> {
+        let rows = parse(input)?;
+        if rows.len() < 2 {
+            anyhow::bail!("there's a header row but no rows to insert");
+        }
+        let columns: Vec<String> = rows[0].iter().map(|c| sql_identifier(c)).collect();
+        let values: Vec<String> = rows[1..]
+            .iter()
+            .map(|row| {
+                let cells: Vec<String> = row.iter().map(|c| sql_value(c)).collect();
+                format!("  ({})", cells.join(", "))
+            })
+            .collect();
+        Ok(format!(
+            "INSERT INTO {} ({}) VALUES\n{};",
+            sql_identifier(&self.table),
+            columns.join(", "),
+            values.join(",\n")
+        ))
+    }
+
+    fn settings(&mut self) -> Vec<Setting<'_>> {
+        vec![Setting::Text {
+            label: "table",
+            value: &mut self.table,
+        }]
+    }
+}
+
+fn cell_text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        // Nested values stay as json, which is the least surprising thing to put in a cell.
+        value => value.to_string(),
+    }
+}
+
+/// The reverse of `TableToJson`. Columns come from the keys of every record, in the order
+/// they're first seen.
+#[derive(Default)]
+pub struct JsonToCsv {
+    /// An index into `DELIMITER_NAMES`.
+    pub delimiter: usize,
+}
+
+impl Transform for JsonToCsv {
+    fn name(&self) -> &str {
+        "json to csv"
+    }
+
+    fn description(&self) -> &str {
+        "convert an array of json objects to csv"
+    }
+
+    fn default_key(&self) -> Option<&str> {
+        None
+    }
+
+    fn apply(&self, input: &str) -> anyhow::Result<String> {
+        let Value::Array(records) = json::parse(input)? else {
+            anyhow::bail!("expected an array of objects");
+        };
+        let mut headers: Vec<String> = vec![];
+        for record in &records {
+            let Value::Object(record) = record else {
+                anyhow::bail!("expected an array of objects, but found {}", record);
+            };
+            for key in record.keys() {
+                if !headers.contains(key) {
+                    headers.push(key.clone());
+                }
+            }
+        }
+        let mut rows = vec![headers.clone()];
+        for record in &records {
+            rows.push(
+                headers
+                    .iter()
+                    .map(|key| record.get(key).map(cell_text).unwrap_or_default())
+                    .collect(),
+            );
+        }
+        let delimiter = match self.delimiter {
+            0 => b',',
+            1 => b'\t',
+            _ => b';',
+        };
+        write(&rows, delimiter)
+    }
+
+    fn settings(&mut self) -> Vec<Setting<'_>> {
+        vec![Setting::Choice {
+            label: "delimiter",
+            value: &mut self.delimiter,
+            options: DELIMITER_NAMES,
+        }]
+    }
+}
+
+/// Splits a Markdown table row on the pipes that aren't escaped.
+fn markdown_cells(line: &str) -> Vec<String> {
+    let line = line.trim();
+    let line = line.strip_prefix('|').unwrap_or(line);
+    let line = match line.strip_suffix('|') {
+        Some(rest) if !rest.ends_with('\\') => rest,
+        _ => line,
+    };
+    let mut cells = vec![String::new()];
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if chars.peek() == Some(&'|') => {
+                cells.last_mut().unwrap().push('|');
+                chars.next();
+            }
+            '|' => cells.push(String::new()),
+            c => cells.last_mut().unwrap().push(c),
+        }
+    }
+    cells
+        .into_iter()
+        .map(|cell| cell.trim().replace("<br>", "\n"))
+        .collect()
+}
+
+fn separator_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| Regex::new(r"^:?-+:?$").unwrap())
+}
+
+/// The reverse of `TableToMarkdown`, giving TSV that pastes into a spreadsheet.
+pub struct MarkdownToTsv;
+
+impl Transform for MarkdownToTsv {
+    fn name(&self) -> &str {
+        "markdown to tsv"
+    }
+
+    fn description(&self) -> &str {
+        "convert a markdown table to tab separated values"
+    }
+
+    fn default_key(&self) -> Option<&str> {
+        None
+    }
+
+    fn apply(&self, input: &str) -> anyhow::Result<String> {
+        let rows: Vec<Vec<String>> = input
+            .lines()
+            .filter(|line| line.contains('|'))
+            .map(markdown_cells)
+            .filter(|cells| !cells.iter().all(|cell| separator_regex().is_match(cell)))
+            .collect();
+        if rows.is_empty() {
+            anyhow::bail!("there's no markdown table in the buffer");
+        }
+        let columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+        let rows: Vec<Vec<String>> = rows
+            .into_iter()
+            .map(|mut row| {
+                row.resize(columns, String::new());
+                row
+            })
+            .collect();
+        write(&rows, b'\t')
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_delimiters() {
+        assert_eq!(parse("a,b\n1,2").unwrap(), [["a", "b"], ["1", "2"]]);
+        assert_eq!(
+            parse("a\tb,c\n1\t2,3").unwrap(),
+            [["a", "b,c"], ["1", "2,3"]]
+        );
+        assert_eq!(parse("a;b\n1;2").unwrap(), [["a", "b"], ["1", "2"]]);
+        assert_eq!(parse("a,b,c\n1").unwrap()[1], ["1", "", ""]);
+        assert!(parse("").is_err());
+    }
+
+    #[test]
+    fn markdown_round_trips() {
+        let tsv = "name\tcount\na|b\t10\nlong name\t2";
+        let markdown = TableToMarkdown.apply(tsv).unwrap();
+        assert_eq!(
+            markdown,
+            "| name      | count |\n\
+             | --------- | ----: |\n\
+             | a\\|b      |    10 |\n\
+             | long name |     2 |"
+        );
+        assert_eq!(MarkdownToTsv.apply(&markdown).unwrap(), tsv);
+    }
+
+    #[test]
+    fn json_round_trips() {
+        let csv = "id,name,tags\n1,\"Smith, J\",\n2,Ann,x";
+        let json = TableToJson { infer_types: true }.apply(csv).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Value>(&json).unwrap(),
+            serde_json::json!([
+                {"id": 1, "name": "Smith, J", "tags": null},
+                {"id": 2, "name": "Ann", "tags": "x"},
+            ])
+        );
+        assert_eq!(JsonToCsv { delimiter: 0 }.apply(&json).unwrap(), csv);
+        let strings = TableToJson { infer_types: false }.apply(csv).unwrap();
+        assert!(strings.contains("\"id\": \"1\""));
+    }
+
+    #[test]
+    fn detected_numbers_keep_their_text() {
+        let csv = "n\n12345678901234567890123\n1e2\n007";
+        let json = TableToJson { infer_types: true }.apply(csv).unwrap();
+        assert_eq!(
+            json,
+            "[\n  {\n    \"n\": 12345678901234567890123\n  },\n  {\n    \"n\": 1e2\n  },\n  \
+             {\n    \"n\": \"007\"\n  }\n]"
+        );
+        assert_eq!(JsonToCsv { delimiter: 0 }.apply(&json).unwrap(), csv);
+    }
+
+    #[test]
+    fn json_to_csv_needs_objects() {
+        assert!(JsonToCsv { delimiter: 0 }.apply("[1, 2]").is_err());
+        assert!(JsonToCsv { delimiter: 0 }.apply("{}").is_err());
+    }
+
+    #[test]
+    fn sql_insert() {
+        let csv = "id,full name,note\n1,O'Brien,\n2.5,Ann,x";
+        assert_eq!(
+            TableToSql::default().apply(csv).unwrap(),
+            "INSERT INTO table_name (id, \"full name\", note) VALUES\n  \
+             (1, 'O''Brien', NULL),\n  \
+             (2.5, 'Ann', 'x');"
+        );
+        assert!(TableToSql::default().apply("a,b").is_err());
+    }
+
+    #[test]
+    fn sql_quotes_numbers_that_would_change() {
+        for cell in [
+            "01234",
+            "+44",
+            "00.5",
+            "12345678901234567890",
+            "3.14159265358979323",
+            "inf",
+            "NaN",
+        ] {
+            assert_eq!(sql_value(cell), format!("'{}'", cell), "{}", cell);
+        }
+        for cell in [
+            "0",
+            "-7",
+            "0.5",
+            ".5",
+            "-0.25",
+            "1e5",
+            "9223372036854775807",
+            "0.000123",
+        ] {
+            assert_eq!(sql_value(cell), cell, "{}", cell);
+        }
+    }
+}
@@ -0,0 +1,310 @@
+use std::ops::Range;
+use std::sync::OnceLock;
+
+use ::regex::Regex;
+use chrono::{DateTime, Datelike, Local, NaiveDateTime, SecondsFormat, Utc};
+
+use crate::{Setting, Transform};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Unit {
+    Seconds,
+    Milliseconds,
+    Microseconds,
+    Nanoseconds,
+}
+
+const UNITS: &[&str] = &["seconds", "milliseconds", "microseconds", "nanoseconds"];
+
+impl Unit {
+    pub fn name(self) -> &'static str {
+        UNITS[self as usize]
+    }
+
+    fn per_second(self) -> i64 {
+        match self {
+            Unit::Seconds => 1,
+            Unit::Milliseconds => 1_000,
+            Unit::Microseconds => 1_000_000,
+            Unit::Nanoseconds => 1_000_000_000,
+        }
+    }
+
+    /// Enough fractional digits to show everything the unit can express.
+    fn seconds_format(self) -> SecondsFormat {
+        match self {
+            Unit::Seconds => SecondsFormat::Secs,
+            Unit::Milliseconds => SecondsFormat::Millis,
+            Unit::Microseconds => SecondsFormat::Micros,
+            Unit::Nanoseconds => SecondsFormat::Nanos,
+        }
+    }
+}
+
+/// An epoch timestamp found in some text.
+pub struct Found {
+    /// Byte offsets of the timestamp in the text.
+    pub range: Range<usize>,
+    pub unit: Unit,
+    pub date: DateTime<Utc>,
+    format: SecondsFormat,
+}
+
+impl Found {
+    pub fn utc(&self) -> String {
+        self.date.to_rfc3339_opts(self.format, true)
+    }
+
+    pub fn local(&self) -> String {
+        self.date
+            .with_timezone(&Local)
+            .to_rfc3339_opts(self.format, false)
+    }
+}
+
+fn timestamp_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| Regex::new(r"\b([0-9]{9,19})(?:\.([0-9]{1,9}))?\b").unwrap())
+}
+
+/// Finds integers that look like epoch timestamps. The unit is guessed from the number of
+/// digits, and only dates between 1973 and 2100 count, which keeps out most ids and counters.
+/// Seconds can have a fractional part, like `1700000000.25`.
+pub fn find(input: &str) -> Vec<Found> {
+    timestamp_regex()
+        .captures_iter(input)
+        .filter_map(|captures| {
+            let whole = captures.get(0)?;
+            let digits = captures.get(1)?.as_str();
+            let unit = match digits.len() {
+                9..=10 => Unit::Seconds,
+                11..=13 => Unit::Milliseconds,
+                14..=16 => Unit::Microseconds,
+                _ => Unit::Nanoseconds,
+            };
+            let value: i64 = digits.parse().ok()?;
+            let (date, format) = match captures.get(2) {
+                Some(fraction) if unit == Unit::Seconds => {
+                    let nanos: u32 = format!("{:0<9}", fraction.as_str()).parse().ok()?;
+                    (
+                        DateTime::from_timestamp(value, nanos)?,
+                        SecondsFormat::AutoSi,
+                    )
+                }
+                // A fraction of a millisecond isn't a format anyone uses.
+                Some(_) => return None,
+                None => {
+                    let per_second = unit.per_second();
+                    let nanos = (value % per_second) * (1_000_000_000 / per_second);
+                    (
+                        DateTime::from_timestamp(value / per_second, nanos as u32)?,
+                        unit.seconds_format(),
+                    )
+                }
+            };
+            (1973..=2100).contains(&date.year()).then_some(Found {
+                range: whole.range(),
+                unit,
+                date,
+                format,
+            })
+        })
+        .collect()
+}
+
+/// A few lines describing `found`, for a tooltip.
+pub fn describe(found: &Found) -> String {
+    format!(
+        "{} since the epoch\n{}\n{}",
+        found.unit.name(),
+        found.utc(),
+        found.local()
+    )
+}
+
+pub struct TimestampsToDates {
+    pub local: bool,
+}
+
+impl Transform for TimestampsToDates {
+    fn name(&self) -> &str {
+        "timestamps to dates"
+    }
+
+    fn description(&self) -> &str {
+        "convert epoch timestamps to rfc 3339 dates"
+    }
+
+    fn default_key(&self) -> Option<&str> {
+        None
+    }
+
+    fn apply(&self, input: &str) -> anyhow::Result<String> {
+        let mut out = String::with_capacity(input.len());
+        let mut last = 0;
+        for found in find(input) {
+            out.push_str(&input[last..found.range.start]);
+            out.push_str(&if self.local {
+                found.local()
+            } else {
+                found.utc()
+            });
+            last = found.range.end;
+        }
+        out.push_str(&input[last..]);
+        Ok(out)
+    }
+
+    fn settings(&mut self) -> Vec<Setting<'_>> {
+        vec![Setting::Toggle {
+            label: "local time",
+            value: &mut self.local,
+        }]
+    }
+}
+
+fn date_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| {
+        Regex::new(
+            r"\b([0-9]{4}-[0-9]{2}-[0-9]{2})[Tt ]([0-9]{2}:[0-9]{2}:[0-9]{2}(?:[.,][0-9]{1,9})?)(Z|z|[+-][0-9]{2}:?[0-9]{2})?",
+        )
+        .unwrap()
+    })
+}
+
+/// Parses the pieces `date_regex` captures. Dates without an offset are taken to be UTC, since
+/// that's what logs without one are usually in.
+fn parse_date(date: &str, time: &str, offset: Option<&str>) -> Option<DateTime<Utc>> {
+    let naive =
+        NaiveDateTime::parse_from_str(&format!("{}T{}", date, time.replace(',', ".")), "%FT%T%.f")
+            .ok()?;
+    let Some(offset) = offset.filter(|o| !o.eq_ignore_ascii_case("z")) else {
+        return Some(naive.and_utc());
+    };
+    let offset = offset.replace(':', "");
+    let sign = if offset.starts_with('-') { -1 } else { 1 };
+    let hours: i32 = offset.get(1..3)?.parse().ok()?;
+    let minutes: i32 = offset.get(3..5)?.parse().ok()?;
+    let offset = chrono::FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))?;
+    Some(naive.and_local_timezone(offset).single()?.to_utc())
+}
+
+/// The reverse of `TimestampsToDates`: finds RFC 3339 (and ISO 8601-ish) dates and replaces
+/// them with epoch timestamps.
+#[derive(Default)]
+pub struct DatesToTimestamps {
+    /// An index into `UNITS`.
+    pub unit: usize,
+}
+
+impl Transform for DatesToTimestamps {
+    fn name(&self) -> &str {
+        "dates to timestamps"
+    }
+
+    fn description(&self) -> &str {
+        "convert rfc 3339 dates to epoch timestamps"
+    }
+
+    fn default_key(&self) -> Option<&str> {
+        None
+    }
+
+    fn apply(&self, input: &str) -> anyhow::Result<String> {
+        Ok(date_regex()
+            .replace_all(input, |captures: &::regex::Captures| {
+                let parsed = parse_date(
+                    &captures[1],
+                    &captures[2],
+                    captures.get(3).map(|m| m.as_str()),
+                );
+                let Some(date) = parsed else {
+                    return captures[0].to_string();
+                };
+                match self.unit {
+                    0 => date.timestamp().to_string(),
+                    1 => date.timestamp_millis().to_string(),
+                    2 => date.timestamp_micros().to_string(),
+                    _ => date
+                        .timestamp_nanos_opt()
+                        .map_or_else(|| captures[0].to_string(), |n| n.to_string()),
+                }
+            })
+            .into_owned())
+    }
+
+    fn settings(&mut self) -> Vec<Setting<'_>> {
+        vec![Setting::Choice {
+            label: "unit",
+            value: &mut self.unit,
+            options: UNITS,
+        }]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_each_unit() {
+        let found = find("at 1700000000, 1700000000123 and 1700000000123456789.");
+        let units: Vec<Unit> = found.iter().map(|f| f.unit).collect();
+        assert_eq!(
+            units,
+            [Unit::Seconds, Unit::Milliseconds, Unit::Nanoseconds]
+        );
+        assert_eq!(found[0].range, 3..13);
+        assert_eq!(found[0].utc(), "2023-11-14T22:13:20Z");
+        assert_eq!(found[1].utc(), "2023-11-14T22:13:20.123Z");
+        assert_eq!(found[2].utc(), "2023-11-14T22:13:20.123456789Z");
+    }
+
+    #[test]
+    fn skips_numbers_that_arent_dates() {
+        assert!(find("id 99999999999999999999 and 0000000001").is_empty());
+        assert_eq!(find("x1700000000 1700000000").len(), 1);
+        // Non-ASCII digits aren't timestamps.
+        assert!(find("१७००००००००").is_empty());
+        assert_eq!(find("1700000000.25")[0].utc(), "2023-11-14T22:13:20.250Z");
+    }
+
+    #[test]
+    fn dates_round_trip() {
+        let seconds = TimestampsToDates { local: false }
+            .apply("t=1700000000")
+            .unwrap();
+        assert_eq!(seconds, "t=2023-11-14T22:13:20Z");
+        assert_eq!(
+            DatesToTimestamps::default().apply(&seconds).unwrap(),
+            "t=1700000000"
+        );
+    }
+
+    #[test]
+    fn parses_offsets_and_fractions() {
+        let to_millis = DatesToTimestamps { unit: 1 };
+        for date in [
+            "2023-11-14T22:13:20.5Z",
+            "2023-11-14 23:13:20,5+01:00",
+            "2023-11-14T17:43:20.500-0430",
+            "2023-11-14t22:13:20.5",
+        ] {
+            assert_eq!(to_millis.apply(date).unwrap(), "1700000000500", "{}", date);
+        }
+    }
+
+    #[test]
+    fn leaves_bad_dates_alone() {
+        let to_seconds = DatesToTimestamps::default();
+        for date in ["2023-13-01T00:00:00Z", "２０２４-01-01T00:00:00Z"] {
+            assert_eq!(to_seconds.apply(date).unwrap(), date);
+        }
+        // An offset in other digits isn't an offset, so the date is taken as UTC.
+        assert_eq!(
+            to_seconds.apply("2024-01-01T00:00:00+०५:००").unwrap(),
+            "1704067200+०५:००"
+        );
+    }
+}
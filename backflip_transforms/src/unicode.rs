@@ -0,0 +1,342 @@
+use unicode_general_category::{get_general_category, GeneralCategory};
+use unicode_normalization::UnicodeNormalization;
+
+use crate::{Setting, Transform};
+
+/// One character of the buffer, described.
+pub struct CharInfo {
+    /// Byte offset of the character in the text.
+    pub offset: usize,
+    pub c: char,
+    pub name: String,
+    /// The two letter general category, like `Lu`.
+    pub category: &'static str,
+    /// The UTF-8 encoding, as hex bytes separated by spaces.
+    pub utf8: String,
+    /// What kind of invisible character this is, if it's one that causes trouble.
+    pub invisible: Option<Invisible>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Invisible {
+    ZeroWidth,
+    ByteOrderMark,
+    Bidi,
+    NonBreakingSpace,
+    SoftHyphen,
+    Control,
+    /// Any other format character, like the invisible math operators.
+    Format,
+}
+
+impl Invisible {
+    pub fn name(self) -> &'static str {
+        match self {
+            Invisible::ZeroWidth => "zero width",
+            Invisible::ByteOrderMark => "byte order mark",
+            Invisible::Bidi => "bidi control",
+            Invisible::NonBreakingSpace => "non-breaking space",
+            Invisible::SoftHyphen => "soft hyphen",
+            Invisible::Control => "control",
+            Invisible::Format => "format",
+        }
+    }
+}
+
+/// Characters that are hard or impossible to see, but change what text means to a parser.
+pub fn invisible(c: char) -> Option<Invisible> {
+    Some(match c {
+        '\u{200B}' | '\u{200C}' | '\u{200D}' | '\u{2060}' | '\u{180E}' => Invisible::ZeroWidth,
+        '\u{FEFF}' => Invisible::ByteOrderMark,
+        '\u{200E}'
+        | '\u{200F}'
+        | '\u{061C}'
+        | '\u{202A}'..='\u{202E}'
+        | '\u{2066}'..='\u{2069}' => Invisible::Bidi,
+        '\u{00A0}' | '\u{2007}' | '\u{202F}' => Invisible::NonBreakingSpace,
+        '\u{00AD}' => Invisible::SoftHyphen,
+        // Tab, newline and carriage return are visible enough.
+        '\t' | '\n' | '\r' => return None,
+        c => match get_general_category(c) {
+            GeneralCategory::Control => Invisible::Control,
+            GeneralCategory::Format => Invisible::Format,
+            _ => return None,
+        },
+    })
+}
+
+fn category(c: char) -> &'static str {
+    use GeneralCategory::*;
+    match get_general_category(c) {
+        UppercaseLetter => "Lu",
+        LowercaseLetter => "Ll",
+        TitlecaseLetter => "Lt",
+        ModifierLetter => "Lm",
+        OtherLetter => "Lo",
+        NonspacingMark => "Mn",
+        SpacingMark => "Mc",
+        EnclosingMark => "Me",
+        DecimalNumber => "Nd",
+        LetterNumber => "Nl",
+        OtherNumber => "No",
+        ConnectorPunctuation => "Pc",
+        DashPunctuation => "Pd",
+        OpenPunctuation => "Ps",
+        ClosePunctuation => "Pe",
+        InitialPunctuation => "Pi",
+        FinalPunctuation => "Pf",
+        OtherPunctuation => "Po",
+        MathSymbol => "Sm",
+        CurrencySymbol => "Sc",
+        ModifierSymbol => "Sk",
+        OtherSymbol => "So",
+        SpaceSeparator => "Zs",
+        LineSeparator => "Zl",
+        ParagraphSeparator => "Zp",
+        Control => "Cc",
+        Format => "Cf",
+        Surrogate => "Cs",
+        PrivateUse => "Co",
+        Unassigned => "Cn",
+    }
+}
+
+/// The full name of a general category abbreviation.
+pub fn category_name(abbreviation: &str) -> &'static str {
+    match abbreviation {
+        "Lu" => "uppercase letter",
+        "Ll" => "lowercase letter",
+        "Lt" => "titlecase letter",
+        "Lm" => "modifier letter",
+        "Lo" => "other letter",
+        "Mn" => "nonspacing mark",
+        "Mc" => "spacing mark",
+        "Me" => "enclosing mark",
+        "Nd" => "decimal number",
+        "Nl" => "letter number",
+        "No" => "other number",
+        "Pc" => "connector punctuation",
+        "Pd" => "dash punctuation",
+        "Ps" => "open punctuation",
+        "Pe" => "close punctuation",
+        "Pi" => "initial punctuation",
+        "Pf" => "final punctuation",
+        "Po" => "other punctuation",
+        "Sm" => "math symbol",
+        "Sc" => "currency symbol",
+        "Sk" => "modifier symbol",
+        "So" => "other symbol",
+        "Zs" => "space separator",
+        "Zl" => "line separator",
+        "Zp" => "paragraph separator",
+        "Cc" => "control",
+        "Cf" => "format",
+        "Cs" => "surrogate",
+        "Co" => "private use",
+        _ => "unassigned",
+    }
+}
+
+pub fn describe(offset: usize, c: char) -> CharInfo {
+    let name = unicode_names2::name(c).map_or_else(
+        // Control characters don't have names, only aliases.
+        || format!("<{}>", category_name(category(c))),
+        |name| name.to_string(),
+    );
+    CharInfo {
+        offset,
+        c,
+        name,
+        category: category(c),
+        utf8: c
+            .encode_utf8(&mut [0; 4])
+            .bytes()
+            .map(|b| format!("{:02x}", b))
+            .collect::<Vec<_>>()
+            .join(" "),
+        invisible: invisible(c),
+    }
+}
+
+pub fn inspect(input: &str) -> Vec<CharInfo> {
+    input.char_indices().map(|(i, c)| describe(i, c)).collect()
+}
+
+#[derive(Clone, Copy)]
+pub enum Form {
+    Nfc,
+    Nfd,
+    Nfkc,
+    Nfkd,
+}
+
+/// Unicode normalization, for text that looks the same but doesn't compare equal.
+pub struct Normalize(pub Form);
+
+impl Transform for Normalize {
+    fn name(&self) -> &str {
+        match self.0 {
+            Form::Nfc => "nfc normalize",
+            Form::Nfd => "nfd normalize",
+            Form::Nfkc => "nfkc normalize",
+            Form::Nfkd => "nfkd normalize",
+        }
+    }
+
+    fn description(&self) -> &str {
+        match self.0 {
+            Form::Nfc => "compose characters with their accents",
+            Form::Nfd => "decompose characters into base characters and accents",
+            Form::Nfkc => "compose, replacing compatibility characters like ﬁ and ²",
+            Form::Nfkd => "decompose, replacing compatibility characters like ﬁ and ²",
+        }
+    }
+
+    fn default_key(&self) -> Option<&str> {
+        None
+    }
+
+    fn apply(&self, input: &str) -> anyhow::Result<String> {
+        Ok(match self.0 {
+            Form::Nfc => input.nfc().collect(),
+            Form::Nfd => input.nfd().collect(),
+            Form::Nfkc => input.nfkc().collect(),
+            Form::Nfkd => input.nfkd().collect(),
+        })
+    }
+}
+
+/// Whether a zero width joiner between `prev` and `next` is holding an emoji sequence like
+/// 👩‍💻 together, rather than being noise.
+fn joins_emoji(prev: Option<char>, next: Option<char>) -> bool {
+    let emoji = |c: Option<char>| {
+        c.is_some_and(|c| {
+            // Variation selector 16 and the skin tone modifiers end an emoji too.
+            c == '\u{FE0F}'
+                || ('\u{1F3FB}'..='\u{1F3FF}').contains(&c)
+                || get_general_category(c) == GeneralCategory::OtherSymbol
+        })
+    };
+    emoji(prev) && emoji(next)
+}
+
+/// Removes the characters `invisible` flags, except that non-breaking spaces become ordinary
+/// spaces so words stay apart.
+pub struct StripInvisibles {
+    /// Leave control characters (other than the bidi ones) alone.
+    pub keep_controls: bool,
+}
+
+impl Transform for StripInvisibles {
+    fn name(&self) -> &str {
+        "strip invisibles"
+    }
+
+    fn description(&self) -> &str {
+        "remove zero width spaces, byte order marks and bidi controls"
+    }
+
+    fn default_key(&self) -> Option<&str> {
+        None
+    }
+
+    fn apply(&self, input: &str) -> anyhow::Result<String> {
+        let chars: Vec<char> = input.chars().collect();
+        let mut out = String::with_capacity(input.len());
+        for (i, &c) in chars.iter().enumerate() {
+            match invisible(c) {
+                None => out.push(c),
+                Some(Invisible::NonBreakingSpace) => out.push(' '),
+                Some(Invisible::Control) if self.keep_controls => out.push(c),
+                Some(_) if c == '\u{200D}' => {
+                    let prev = i.checked_sub(1).map(|i| chars[i]);
+                    if joins_emoji(prev, chars.get(i + 1).copied()) {
+                        out.push(c);
+                    }
+                }
+                Some(_) => (),
+            }
+        }
+        Ok(out)
+    }
+
+    fn settings(&mut self) -> Vec<Setting<'_>> {
+        vec![Setting::Toggle {
+            label: "keep control characters",
+            value: &mut self.keep_controls,
+        }]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalization_forms() {
+        let composed = "caf\u{e9} \u{fb01}";
+        let decomposed = Normalize(Form::Nfd).apply(composed).unwrap();
+        assert_eq!(decomposed, "cafe\u{301} \u{fb01}");
+        assert_eq!(Normalize(Form::Nfc).apply(&decomposed).unwrap(), composed);
+        assert_eq!(
+            Normalize(Form::Nfkc).apply(composed).unwrap(),
+            "caf\u{e9} fi"
+        );
+        assert_eq!(
+            Normalize(Form::Nfkd).apply(composed).unwrap(),
+            "cafe\u{301} fi"
+        );
+    }
+
+    #[test]
+    fn classifies_invisibles() {
+        assert_eq!(invisible('\u{200B}'), Some(Invisible::ZeroWidth));
+        assert_eq!(invisible('\u{FEFF}'), Some(Invisible::ByteOrderMark));
+        assert_eq!(invisible('\u{202E}'), Some(Invisible::Bidi));
+        assert_eq!(invisible('\u{A0}'), Some(Invisible::NonBreakingSpace));
+        assert_eq!(invisible('\u{AD}'), Some(Invisible::SoftHyphen));
+        assert_eq!(invisible('\u{7}'), Some(Invisible::Control));
+        assert_eq!(invisible('\u{2062}'), Some(Invisible::Format));
+        for c in ['\t', '\n', '\r', ' ', 'a', '日'] {
+            assert_eq!(invisible(c), None);
+        }
+    }
+
+    #[test]
+    fn describes_characters() {
+        let info = describe(3, 'A');
+        assert_eq!(info.offset, 3);
+        assert_eq!(info.name, "LATIN CAPITAL LETTER A");
+        assert_eq!(info.category, "Lu");
+        assert_eq!(info.utf8, "41");
+        let info = describe(0, '\u{7}');
+        assert_eq!(info.name, "<control>");
+        assert_eq!(info.category, "Cc");
+        assert_eq!(describe(0, '€').utf8, "e2 82 ac");
+    }
+
+    #[test]
+    fn inspect_reports_byte_offsets() {
+        let offsets: Vec<usize> = inspect("aé😀b").iter().map(|info| info.offset).collect();
+        assert_eq!(offsets, [0, 1, 3, 7]);
+        assert!(inspect("").is_empty());
+    }
+
+    #[test]
+    fn strips_invisibles() {
+        let text = "\u{FEFF}a\u{200B}b\u{A0}c\u{7}\u{202E}d";
+        let strip = |keep_controls| StripInvisibles { keep_controls }.apply(text).unwrap();
+        assert_eq!(strip(false), "ab cd");
+        assert_eq!(strip(true), "ab c\u{7}d");
+    }
+
+    #[test]
+    fn keeps_joiners_inside_emoji() {
+        let coder = "\u{1F469}\u{200D}\u{1F4BB}";
+        let strip = StripInvisibles {
+            keep_controls: false,
+        };
+        assert_eq!(strip.apply(coder).unwrap(), coder);
+        assert_eq!(strip.apply("a\u{200D}b").unwrap(), "ab");
+    }
+}
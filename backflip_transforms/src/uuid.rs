@@ -0,0 +1,324 @@
+use std::sync::OnceLock;
+
+use ::regex::{Captures, Match, Regex};
+use ::uuid::{Uuid, Variant};
+
+use crate::{Setting, Transform};
+
+const VERSIONS: &[&str] = &["v4 (random)", "v7 (time ordered)"];
+
+const FORMATS: &[&str] = &[
+    "hyphenated",
+    "simple",
+    "braced",
+    "urn",
+    "windows bytes",
+    "rust bytes",
+];
+
+fn uuid_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| {
+        let hyphenated = r"[0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{12}";
+        Regex::new(&format!(
+            concat!(
+                r"(?i)(?P<text>\{{{0}\}}|(?:urn:uuid:)?\b{0}\b)|(?P<simple>\b[0-9a-f]{{32}}\b)",
+                r"|(?P<rust>\[\s*(?:0x[0-9a-f]{{1,2}}\s*,\s*){{15}}0x[0-9a-f]{{1,2}}\s*,?\s*\])",
+                r"|(?P<windows>\b(?:[0-9a-f]{{2}} ){{15}}[0-9a-f]{{2}}\b)",
+            ),
+            hyphenated
+        ))
+        .unwrap()
+    })
+}
+
+/// Whether the match is alone on its line, apart from whitespace.
+fn alone_on_line(input: &str, found: &Match) -> bool {
+    let start = input[..found.start()].rfind('\n').map_or(0, |i| i + 1);
+    let end = input[found.end()..]
+        .find('\n')
+        .map_or(input.len(), |i| found.end() + i);
+    input[start..found.start()].trim().is_empty() && input[found.end()..end].trim().is_empty()
+}
+
+/// Keeps a uuid read from bare hex only if it's alone on its line and has the version and
+/// variant bits of a real one.
+fn guarded(input: &str, found: &Match, uuid: Uuid) -> Option<Uuid> {
+    let real = uuid.get_variant() == Variant::RFC4122 && (1..=8).contains(&uuid.get_version_num());
+    (real && alone_on_line(input, found)).then_some(uuid)
+}
+
+/// Reads a uuid out of a match of `uuid_regex`. Any 32 hex digits look like a simple uuid, MD5
+/// hashes included, and any 16 hex bytes look like a byte form, hex dump lines included, so
+/// those only count when `guarded` lets them through.
+fn parse(input: &str, captures: &Captures) -> Option<Uuid> {
+    let hex_bytes = |text: &str| -> Option<[u8; 16]> {
+        let bytes = text
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .filter(|byte| !byte.is_empty())
+            .map(|byte| {
+                let byte = byte.trim_start_matches("0x").trim_start_matches("0X");
+                u8::from_str_radix(byte, 16).ok()
+            })
+            .collect::<Option<Vec<u8>>>()?;
+        bytes.try_into().ok()
+    };
+    if let Some(text) = captures.name("text") {
+        Uuid::try_parse(text.as_str()).ok()
+    } else if let Some(simple) = captures.name("simple") {
+        guarded(input, &simple, Uuid::try_parse(simple.as_str()).ok()?)
+    } else if let Some(rust) = captures.name("rust") {
+        guarded(input, &rust, Uuid::from_bytes(hex_bytes(rust.as_str())?))
+    } else {
+        let windows = captures.name("windows")?;
+        guarded(
+            input,
+            &windows,
+            Uuid::from_bytes_le(hex_bytes(windows.as_str())?),
+        )
+    }
+}
+
+/// Writes a uuid in one of `FORMATS`. The byte formats are lists of hex bytes: "windows bytes" is
+/// the order a `GUID` struct has in memory, with the first three fields little-endian, and "rust
+/// bytes" is a `[u8; 16]` literal in the standard big-endian order that `Uuid::from_bytes` takes.
+pub fn write(uuid: &Uuid, format: usize, uppercase: bool) -> String {
+    let text = match FORMATS[format] {
+        "simple" => uuid.simple().to_string(),
+        "braced" => uuid.braced().to_string(),
+        "urn" => uuid.urn().to_string(),
+        "windows bytes" => uuid
+            .to_bytes_le()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect::<Vec<_>>()
+            .join(" "),
+        "rust bytes" => format!(
+            "[{}]",
+            uuid.as_bytes()
+                .iter()
+                .map(|b| format!("0x{:02x}", b))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        _ => uuid.hyphenated().to_string(),
+    };
+    if !uppercase {
+        return text;
+    }
+    // Keep the urn prefix and 0x lowercase, as everything that reads them expects.
+    match text.strip_prefix("urn:uuid:") {
+        Some(rest) => format!("urn:uuid:{}", rest.to_uppercase()),
+        None => text.to_uppercase().replace("0X", "0x"),
+    }
+}
+
+/// Replaces the buffer with freshly generated uuids, one per line.
+pub struct GenerateUuid {
+    /// An index into `VERSIONS`.
+    pub version: usize,
+    pub count: usize,
+    /// An index into `FORMATS`.
+    pub format: usize,
+    pub uppercase: bool,
+}
+
+impl Default for GenerateUuid {
+    fn default() -> Self {
+        GenerateUuid {
+            version: 0,
+            count: 1,
+            format: 0,
+            uppercase: false,
+        }
+    }
+}
+
+impl Transform for GenerateUuid {
+    fn name(&self) -> &str {
+        "generate uuid"
+    }
+
+    fn description(&self) -> &str {
+        "replace the text with new v4 or v7 uuids"
+    }
+
+    fn default_key(&self) -> Option<&str> {
+        None
+    }
+
+    fn apply(&self, _input: &str) -> anyhow::Result<String> {
+        Ok((0..self.count)
+            .map(|_| {
+                let uuid = match self.version {
+                    0 => Uuid::new_v4(),
+                    _ => Uuid::now_v7(),
+                };
+                write(&uuid, self.format, self.uppercase)
+            })
+            .collect::<Vec<_>>()
+            .join("\n"))
+    }
+
+    fn settings(&mut self) -> Vec<Setting<'_>> {
+        vec![
+            Setting::Choice {
+                label: "version",
+                value: &mut self.version,
+                options: VERSIONS,
+            },
+            Setting::Number {
+                label: "count",
+                value: &mut self.count,
+                range: 1..=1000,
+            },
+            Setting::Choice {
+                label: "format",
+                value: &mut self.format,
+                options: FORMATS,
+            },
+            Setting::Toggle {
+                label: "uppercase",
+                value: &mut self.uppercase,
+            },
+        ]
+    }
+}
+
+/// Rewrites every uuid in the buffer in another format. Uuids are recognized in any of the
+/// formats `write` produces, so this converts between all of them, though the simple and byte
+/// forms are only picked up alone on a line.
+#[derive(Default)]
+pub struct FormatUuid {
+    /// An index into `FORMATS`.
+    pub format: usize,
+    pub uppercase: bool,
+}
+
+impl Transform for FormatUuid {
+    fn name(&self) -> &str {
+        "format uuid"
+    }
+
+    fn description(&self) -> &str {
+        "reformat uuids as braced, urn, guid bytes and more"
+    }
+
+    fn default_key(&self) -> Option<&str> {
+        None
+    }
+
+    fn apply(&self, input: &str) -> anyhow::Result<String> {
+        let mut found = false;
+        let out =
+            uuid_regex().replace_all(input, |captures: &Captures| match parse(input, captures) {
+                Some(uuid) => {
+                    found = true;
+                    write(&uuid, self.format, self.uppercase)
+                }
+                None => captures[0].to_string(),
+            });
+        if !found {
+            anyhow::bail!("there are no uuids in the buffer");
+        }
+        Ok(out.into_owned())
+    }
+
+    fn settings(&mut self) -> Vec<Setting<'_>> {
+        vec![
+            Setting::Choice {
+                label: "format",
+                value: &mut self.format,
+                options: FORMATS,
+            },
+            Setting::Toggle {
+                label: "uppercase",
+                value: &mut self.uppercase,
+            },
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HYPHENATED: &str = "67e55044-10b1-426f-9247-bb680e5fe0c8";
+
+    fn format(input: &str, format: usize) -> anyhow::Result<String> {
+        FormatUuid {
+            format,
+            uppercase: false,
+        }
+        .apply(input)
+    }
+
+    #[test]
+    fn every_format_round_trips() {
+        let uuid = Uuid::parse_str(HYPHENATED).unwrap();
+        for (from, name) in FORMATS.iter().enumerate() {
+            let written = format(HYPHENATED, from).unwrap();
+            for to in 0..FORMATS.len() {
+                assert_eq!(format(&written, to).unwrap(), write(&uuid, to, false));
+            }
+            assert_eq!(format(&written, 0).unwrap(), HYPHENATED, "{}", name);
+        }
+    }
+
+    #[test]
+    fn writes_each_format() {
+        let uuid = Uuid::parse_str(HYPHENATED).unwrap();
+        assert_eq!(write(&uuid, 1, true), "67E5504410B1426F9247BB680E5FE0C8");
+        assert_eq!(write(&uuid, 2, false), format!("{{{}}}", HYPHENATED));
+        assert_eq!(
+            write(&uuid, 3, true),
+            "urn:uuid:67E55044-10B1-426F-9247-BB680E5FE0C8"
+        );
+        assert!(write(&uuid, 4, false).starts_with("44 50 e5 67 b1 10 6f 42 92"));
+        assert!(write(&uuid, 5, true).starts_with("[0x67, 0xE5, 0x50, 0x44, 0x10,"));
+    }
+
+    #[test]
+    fn leaves_hashes_alone() {
+        let md5 = "d41d8cd98f00b204e9800998ecf8427e";
+        assert!(format(md5, 0).is_err());
+        let v4 = "67e5504410b1426f9247bb680e5fe0c8";
+        assert!(format(&format!("etag {}", v4), 0).is_err());
+        assert_eq!(
+            format(&format!(" {}\n", v4), 0).unwrap(),
+            format!(" {}\n", HYPHENATED)
+        );
+        assert_eq!(
+            format(&format!("{}\n{}  file", HYPHENATED, md5), 1).unwrap(),
+            format!("{}\n{}  file", v4, md5)
+        );
+    }
+
+    #[test]
+    fn leaves_hex_dumps_alone() {
+        let dump = "00000000: 68 65 6c 6c 6f 2c 20 77 6f 72 6c 64 21 0a 00 01  hello, world!...";
+        assert_eq!(
+            format(dump, 0).unwrap_err().to_string(),
+            "there are no uuids in the buffer"
+        );
+        let bytes = "68 65 6c 6c 6f 2c 20 77 6f 72 6c 64 21 0a 00 01";
+        assert!(format(bytes, 0).is_err());
+        let rust = write(&Uuid::parse_str(HYPHENATED).unwrap(), 5, false);
+        assert!(format(&format!("let id = {};", rust), 0).is_err());
+    }
+
+    #[test]
+    fn generates_uuids() {
+        let generate = GenerateUuid {
+            version: 1,
+            count: 3,
+            ..GenerateUuid::default()
+        };
+        let text = generate.apply("").unwrap();
+        let uuids: Vec<Uuid> = text.lines().map(|l| Uuid::parse_str(l).unwrap()).collect();
+        assert_eq!(uuids.len(), 3);
+        assert!(uuids.iter().all(|uuid| uuid.get_version_num() == 7));
+        let v4 = GenerateUuid::default().apply("").unwrap();
+        assert_eq!(Uuid::parse_str(&v4).unwrap().get_version_num(), 4);
+    }
+}
@@ -0,0 +1,551 @@
+//! XML (and HTML that's close enough to it) formatting, and conversion to and from the json
+//! value model.
+//!
+//! Elements convert to json like this, which is the convention xml2js and friends use:
+//!
+//! - The document is an object with one key, the root element's name.
+//! - Attributes become keys starting with `@`, so `<a href="x"/>` is `{"a": {"@href": "x"}}`.
+//! - An element with only text in it is just the text: `<b>hi</b>` is `{"b": "hi"}`. When it
+//!   also has attributes or children, the text goes under `#text`.
+//! - Child elements become keys named after them. Repeated children become an array, in order.
+//! - An empty element without attributes is `null`.
+//! - All values are strings; numbers aren't guessed at. CDATA counts as text, and comments and
+//!   processing instructions are dropped.
+//!
+//! Going the other way, numbers and booleans become text, and the document has to be an object
+//! with a single key. Whitespace around text is trimmed, and text mixed in between child elements
+//! is joined together, so not every document survives the round trip exactly.
+
+use quick_xml::events::{BytesStart, BytesText, Event};
+use quick_xml::{Reader, Writer};
+use serde_json::{Map, Value};
+
+use crate::{Setting, Transform};
+
+/// Elements that never have content in HTML, so `<br>` doesn't need a `</br>`.
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source",
+    "track", "wbr",
+];
+
+/// Elements whose whitespace matters, so their content is kept exactly as it was written.
+const PREFORMATTED_ELEMENTS: &[&str] = &["pre", "textarea"];
+
+/// Elements whose content is raw text in HTML rather than markup, so a `<` in a script doesn't
+/// start a tag.
+const RAW_TEXT_ELEMENTS: &[&str] = &["script", "style"];
+
+fn is_one_of(names: &[&str], name: &[u8]) -> bool {
+    names
+        .iter()
+        .any(|known| known.as_bytes().eq_ignore_ascii_case(name))
+}
+
+fn is_void(name: &[u8]) -> bool {
+    is_one_of(VOID_ELEMENTS, name)
+}
+
+/// Whether an element's content has to be kept as it is: preformatted elements, and anything
+/// marked `xml:space="preserve"`.
+fn is_preformatted(start: &BytesStart) -> bool {
+    is_one_of(PREFORMATTED_ELEMENTS, start.name().as_ref())
+        || start
+            .try_get_attribute("xml:space")
+            .ok()
+            .flatten()
+            .is_some_and(|space| space.value.as_ref() == b"preserve")
+}
+
+/// Where byte `offset` of `input` is, for error messages.
+fn position(input: &str, offset: usize) -> String {
+    let before = &input[..offset.min(input.len())];
+    let line = before.matches('\n').count() + 1;
+    let column = before.chars().rev().take_while(|&c| c != '\n').count() + 1;
+    format!("line {}, column {}", line, column)
+}
+
+/// Trims the end of the text before a close tag, dropping it if nothing's left.
+fn trim_last_text(events: &mut Vec<Event>) {
+    if let Some(Event::Text(text)) = events.last_mut() {
+        if !text.inplace_trim_end() {
+            return;
+        }
+        events.pop();
+    }
+}
+
+/// Collapses the whitespace around text to at most one space, which keeps the spaces between
+/// words in mixed content like `Hello <b>world</b>` without keeping the indentation. Text that's
+/// only whitespace is dropped if it spans lines, since that's indentation too.
+fn normalize_text(text: &str, after_start: bool) -> Option<String> {
+    let trimmed = text.trim();
+    if trimmed.is_empty() {
+        return (!after_start && !text.contains('\n') && !text.is_empty()).then(|| " ".to_string());
+    }
+    let space = |ws: &str| if ws.is_empty() { "" } else { " " };
+    let leading = if after_start {
+        ""
+    } else {
+        space(&text[..text.len() - text.trim_start().len()])
+    };
+    let trailing = space(&text[text.trim_end().len()..]);
+    Some(format!("{}{}{}", leading, trimmed, trailing))
+}
+
+fn reader(input: &str) -> Reader<&[u8]> {
+    let mut reader = Reader::from_str(input);
+    // The reader would treat `<br>` as needing a close tag, so match tags up here instead.
+    reader.check_end_names(false);
+    reader
+}
+
+/// Reads every event in `input`, checking that the tags match up. HTML void elements come out
+/// as empty elements whether or not they were written as `<br/>`. The content of preformatted
+/// and raw text elements comes out as a single text event, exactly as it was written.
+fn events(input: &str) -> anyhow::Result<Vec<Event<'_>>> {
+    let mut reader = reader(input);
+    // Where `reader` started in `input`. Raw text isn't markup, so the reader skips it by
+    // starting again after it.
+    let mut base = 0;
+    let mut open: Vec<(Vec<u8>, usize)> = vec![];
+    let mut events: Vec<Event> = vec![];
+    // How many elements are open at the one being kept verbatim, and where its content starts.
+    let mut verbatim: Option<(usize, usize)> = None;
+    loop {
+        let start = base + reader.buffer_position();
+        let event = reader.read_event().map_err(|e| {
+            anyhow::anyhow!(
+                "{} at {}",
+                e,
+                position(input, base + reader.buffer_position())
+            )
+        })?;
+        if let Event::Start(e) | Event::Empty(e) = &event {
+            // Attributes are only parsed on demand, so check them now while the position is
+            // known. The html rules allow unquoted values and attributes without one.
+            if let Some(Err(e)) = e.html_attributes().find(Result::is_err) {
+                anyhow::bail!("{} in the tag at {}", e, position(input, start));
+            }
+        }
+        match event {
+            Event::Start(e) if is_void(e.name().as_ref()) => {
+                if verbatim.is_none() {
+                    events.push(Event::Empty(e));
+                }
+            }
+            Event::Start(e) => {
+                open.push((e.name().as_ref().to_vec(), start));
+                if verbatim.is_some() {
+                    continue;
+                }
+                let content = base + reader.buffer_position();
+                if is_one_of(RAW_TEXT_ELEMENTS, e.name().as_ref()) {
+                    let name = String::from_utf8_lossy(e.name().as_ref()).to_ascii_lowercase();
+                    let close = format!("</{}", name);
+                    let Some(length) = input[content..].to_ascii_lowercase().find(&close) else {
+                        anyhow::bail!("<{}> at {} is never closed", name, position(input, start));
+                    };
+                    base = content + length;
+                    reader = self::reader(&input[base..]);
+                    verbatim = Some((open.len(), content));
+                } else if is_preformatted(&e) {
+                    verbatim = Some((open.len(), content));
+                }
+                events.push(Event::Start(e));
+            }
+            Event::End(e) if is_void(e.name().as_ref()) => (),
+            Event::End(e) => {
+                let name = String::from_utf8_lossy(e.name().as_ref()).into_owned();
+                let depth = open.len();
+                match open.pop() {
+                    Some((expected, _)) if expected == e.name().as_ref() => match verbatim {
+                        Some((verbatim_depth, content)) if verbatim_depth == depth => {
+                            let text = BytesText::from_escaped(&input[content..start]);
+                            events.push(Event::Text(text));
+                            events.push(Event::End(e));
+                            verbatim = None;
+                        }
+                        Some(_) => (),
+                        None => {
+                            trim_last_text(&mut events);
+                            events.push(Event::End(e))
+                        }
+                    },
+                    Some((expected, _)) => anyhow::bail!(
+                        "expected </{}> but found </{}> at {}",
+                        String::from_utf8_lossy(&expected),
+                        name,
+                        position(input, start)
+                    ),
+                    None => anyhow::bail!(
+                        "</{}> at {} doesn't close anything",
+                        name,
+                        position(input, start)
+                    ),
+                }
+            }
+            Event::Eof => break,
+            _ if verbatim.is_some() => (),
+            Event::Text(e) => {
+                let after_start = matches!(events.last(), Some(Event::Start(_)) | None);
+                if let Some(text) = normalize_text(std::str::from_utf8(&e)?, after_start) {
+                    events.push(Event::Text(BytesText::from_escaped(text)));
+                }
+            }
+            event => events.push(event),
+        }
+    }
+    if let Some((name, start)) = open.pop() {
+        anyhow::bail!(
+            "<{}> at {} is never closed",
+            String::from_utf8_lossy(&name),
+            position(input, start)
+        );
+    }
+    trim_last_text(&mut events);
+    Ok(events)
+}
+
+fn write_events<'a>(
+    events: impl IntoIterator<Item = Event<'a>>,
+    indent: Option<usize>,
+) -> anyhow::Result<String> {
+    let mut writer = match indent {
+        Some(indent) => Writer::new_with_indent(Vec::new(), b' ', indent),
+        None => Writer::new(Vec::new()),
+    };
+    for event in events {
+        writer.write_event(event)?;
+    }
+    Ok(String::from_utf8(writer.into_inner())?)
+}
+
+/// The text of an element name or attribute key.
+fn name(bytes: &[u8]) -> anyhow::Result<String> {
+    Ok(std::str::from_utf8(bytes)?.to_string())
+}
+
+fn element(start: &BytesStart) -> anyhow::Result<Map<String, Value>> {
+    let mut map = Map::new();
+    for attribute in start.attributes() {
+        let attribute = attribute?;
+        map.insert(
+            format!("@{}", name(attribute.key.as_ref())?),
+            Value::String(attribute.unescape_value()?.into_owned()),
+        );
+    }
+    Ok(map)
+}
+
+fn finish(mut map: Map<String, Value>, text: String) -> Value {
+    match (map.is_empty(), text.is_empty()) {
+        (true, true) => Value::Null,
+        (true, false) => Value::String(text),
+        (false, true) => Value::Object(map),
+        (false, false) => {
+            map.insert("#text".to_string(), Value::String(text));
+            Value::Object(map)
+        }
+    }
+}
+
+/// Adds a child element to `map`, turning repeats into an array.
+fn insert(map: &mut Map<String, Value>, key: String, value: Value) {
+    match map.get_mut(&key) {
+        Some(Value::Array(items)) => items.push(value),
+        Some(existing) => {
+            let first = existing.take();
+            *existing = Value::Array(vec![first, value]);
+        }
+        None => {
+            map.insert(key, value);
+        }
+    }
+}
+
+/// Parses an XML document into the json value model, as described at the top of this module.
+pub fn to_value(input: &str) -> anyhow::Result<Value> {
+    // The root is a pretend element with no name, which collects the real root.
+    let mut stack: Vec<(String, Map<String, Value>, String)> =
+        vec![(String::new(), Map::new(), String::new())];
+    for event in events(input)? {
+        match event {
+            Event::Start(e) => stack.push((name(e.name().as_ref())?, element(&e)?, String::new())),
+            Event::Empty(e) => {
+                let value = finish(element(&e)?, String::new());
+                let (_, parent, _) = stack.last_mut().unwrap();
+                insert(parent, name(e.name().as_ref())?, value);
+            }
+            Event::End(_) => {
+                let (key, map, text) = stack.pop().unwrap();
+                let (_, parent, _) = stack.last_mut().unwrap();
+                insert(parent, key, finish(map, text));
+            }
+            Event::Text(e) => {
+                let text = &mut stack.last_mut().unwrap().2;
+                let piece = e.unescape()?;
+                if !text.is_empty() && !piece.trim().is_empty() {
+                    text.push(' ');
+                }
+                text.push_str(piece.trim());
+            }
+            Event::CData(e) => stack
+                .last_mut()
+                .unwrap()
+                .2
+                .push_str(std::str::from_utf8(&e)?),
+            _ => (),
+        }
+    }
+    let (_, root, _) = stack.pop().unwrap();
+    match root.len() {
+        1 if !root.values().any(Value::is_array) => Ok(Value::Object(root)),
+        1 => anyhow::bail!("there's more than one root element"),
+        0 => anyhow::bail!("there's no root element"),
+        _ => anyhow::bail!("there's more than one root element"),
+    }
+}
+
+fn check_name(name: &str) -> anyhow::Result<()> {
+    let mut chars = name.chars();
+    let valid = chars.next().is_some_and(|c| c.is_alphabetic() || c == '_')
+        && chars.all(|c| c.is_alphanumeric() || "-_.:".contains(c));
+    if !valid {
+        anyhow::bail!("{:?} can't be an element or attribute name", name);
+    }
+    Ok(())
+}
+
+fn scalar(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+fn write_element(events: &mut Vec<Event<'static>>, key: &str, value: &Value) -> anyhow::Result<()> {
+    check_name(key)?;
+    let mut start = BytesStart::new(key.to_string());
+    let text;
+    let mut children = vec![];
+    match value {
+        Value::Null => {
+            events.push(Event::Empty(start));
+            return Ok(());
+        }
+        Value::Array(items) => {
+            for item in items {
+                if item.is_array() {
+                    anyhow::bail!("<{}> has an array directly inside an array", key);
+                }
+                write_element(events, key, item)?;
+            }
+            return Ok(());
+        }
+        Value::Object(map) => {
+            let mut content = None;
+            for (k, v) in map {
+                if let Some(attribute) = k.strip_prefix('@') {
+                    check_name(attribute)?;
+                    let v = scalar(v)
+                        .ok_or_else(|| anyhow::anyhow!("attribute {} isn't a plain value", k))?;
+                    start.push_attribute((attribute, v.as_str()));
+                } else if k == "#text" {
+                    content = scalar(v);
+                } else {
+                    children.push((k, v));
+                }
+            }
+            text = content;
+        }
+        value => text = scalar(value),
+    }
+    if text.is_none() && children.is_empty() {
+        events.push(Event::Empty(start));
+        return Ok(());
+    }
+    let end = start.to_end().into_owned();
+    events.push(Event::Start(start));
+    if let Some(text) = text {
+        events.push(Event::Text(BytesText::new(&text).into_owned()));
+    }
+    for (k, v) in children {
+        write_element(events, k, v)?;
+    }
+    events.push(Event::End(end));
+    Ok(())
+}
+
+/// Writes a json value as XML, indented, using the convention at the top of this module.
+pub fn from_value(value: &Value) -> anyhow::Result<String> {
+    let root = match value {
+        Value::Object(map) if map.len() == 1 => map.iter().next().unwrap(),
+        _ => anyhow::bail!("xml needs an object with a single key for the root element"),
+    };
+    if root.1.is_array() {
+        anyhow::bail!(
+            "xml can only have one root element, but {} is an array",
+            root.0
+        );
+    }
+    let mut events = vec![];
+    write_element(&mut events, root.0, root.1)?;
+    write_events(events, Some(2))
+}
+
+pub struct PrettyXml {
+    pub indent: usize,
+}
+
+impl Default for PrettyXml {
+    fn default() -> Self {
+        PrettyXml { indent: 2 }
+    }
+}
+
+impl Transform for PrettyXml {
+    fn name(&self) -> &str {
+        "pretty print xml"
+    }
+
+    fn description(&self) -> &str {
+        "pretty-print an xml document or html fragment"
+    }
+
+    fn default_key(&self) -> Option<&str> {
+        None
+    }
+
+    fn apply(&self, input: &str) -> anyhow::Result<String> {
+        write_events(events(input)?, Some(self.indent))
+    }
+
+    fn settings(&mut self) -> Vec<Setting<'_>> {
+        vec![Setting::Number {
+            label: "indent",
+            value: &mut self.indent,
+            range: 0..=8,
+        }]
+    }
+}
+
+/// Removes the whitespace between tags, and comments.
+pub struct MinifyXml;
+
+impl Transform for MinifyXml {
+    fn name(&self) -> &str {
+        "minify xml"
+    }
+
+    fn description(&self) -> &str {
+        "remove whitespace and comments from xml or html"
+    }
+
+    fn default_key(&self) -> Option<&str> {
+        None
+    }
+
+    fn apply(&self, input: &str) -> anyhow::Result<String> {
+        let events = events(input)?
+            .into_iter()
+            .filter(|e| !matches!(e, Event::Comment(_)));
+        write_events(events, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn pretty(input: &str) -> String {
+        PrettyXml::default().apply(input).unwrap()
+    }
+
+    #[test]
+    fn pretty_prints_and_minifies() {
+        let input = "<a x=\"1\">\n      <b>hello <i>big</i>   world</b><!-- c --><br><c/></a>";
+        let pretty = pretty(input);
+        assert_eq!(
+            pretty,
+            "<a x=\"1\">\n  <b>hello <i>big</i> world</b>\n  <!-- c -->\n  <br/>\n  <c/>\n</a>"
+        );
+        assert_eq!(
+            MinifyXml.apply(&pretty).unwrap(),
+            "<a x=\"1\"><b>hello <i>big</i> world</b><br/><c/></a>"
+        );
+    }
+
+    #[test]
+    fn keeps_preformatted_whitespace() {
+        let input = "<div>\n<pre>  a\n    <b> b </b>\n</pre><textarea>\n x  y</textarea></div>";
+        assert_eq!(
+            pretty(input),
+            "<div>\n  <pre>  a\n    <b> b </b>\n</pre>\n  <textarea>\n x  y</textarea>\n</div>"
+        );
+        let input = "<doc><code xml:space=\"preserve\">a  <x/>\n  b</code>  <p> c </p></doc>";
+        assert_eq!(
+            MinifyXml.apply(input).unwrap(),
+            "<doc><code xml:space=\"preserve\">a  <x/>\n  b</code> <p>c</p></doc>"
+        );
+        assert_eq!(pretty("<pre></pre>"), "<pre></pre>");
+    }
+
+    #[test]
+    fn passes_raw_text_through() {
+        let script =
+            "<html><script>if (a < b && c) { x = '</p>'; }</script><STYLE>p > a { }</STYLE></html>";
+        assert_eq!(
+            pretty(script),
+            "<html>\n  <script>if (a < b && c) { x = '</p>'; }</script>\n  <STYLE>p > a { }</STYLE>\n</html>"
+        );
+        let e = PrettyXml::default().apply("<script>a < b").unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "<script> at line 1, column 1 is never closed"
+        );
+    }
+
+    #[test]
+    fn reports_mismatched_tags() {
+        let e = MinifyXml.apply("<a>\n  <b></a>").unwrap_err().to_string();
+        assert_eq!(e, "expected </b> but found </a> at line 2, column 6");
+        assert!(MinifyXml.apply("</a>").is_err());
+        assert!(MinifyXml.apply("<a>").is_err());
+        assert!(MinifyXml.apply("<a b=\"1></a>").is_err());
+    }
+
+    #[test]
+    fn converts_to_and_from_json() {
+        let input =
+            "<a id=\"1\"><b>x</b><b>y &amp; z</b><c/><d k=\"v\">t</d><e><![CDATA[<raw>]]></e></a>";
+        let value = to_value(input).unwrap();
+        assert_eq!(
+            value,
+            json!({"a": {
+                "@id": "1",
+                "b": ["x", "y & z"],
+                "c": null,
+                "d": {"@k": "v", "#text": "t"},
+                "e": "<raw>",
+            }})
+        );
+        let xml = from_value(&value).unwrap();
+        assert_eq!(to_value(&xml).unwrap()["a"]["b"], value["a"]["b"]);
+        assert!(xml.contains("<b>y &amp; z</b>"));
+    }
+
+    #[test]
+    fn json_that_isnt_a_document() {
+        assert!(from_value(&json!([1])).is_err());
+        assert!(from_value(&json!({"a": 1, "b": 2})).is_err());
+        assert!(from_value(&json!({"a": [1, 2]})).is_err());
+        assert!(from_value(&json!({"1a": 1})).is_err());
+        assert!(from_value(&json!({"a": {"@x": [1]}})).is_err());
+        assert!(to_value("<!-- nothing -->").is_err());
+        assert!(to_value("<a/><b/>").is_err());
+    }
+}